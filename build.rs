@@ -1,40 +1,229 @@
 use std::env;
+use std::fmt::Write as _;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process;
+
+// build.rs shares these with the crate, so they can only use std and naga,
+// its one build-dependency.
+#[allow(dead_code)]
+#[path = "src/shader/graph.rs"]
+mod graph;
+#[allow(dead_code)]
+#[path = "src/shader/header.rs"]
+mod header;
+#[allow(dead_code)]
+#[path = "src/shader/preprocess.rs"]
+mod preprocess;
+#[allow(dead_code)]
+#[path = "src/shader/uniforms.rs"]
+mod uniforms;
+#[path = "src/shader/validate.rs"]
+mod validate;
+
+const SHADER_DIR: &str = "src/shaders";
+
+/// Shared code for `#include`, under `SHADER_DIR`.
+const LIBRARY_DIR: &str = "lib";
+
+/// The vertex shader every fragment shader is drawn with; it has no header.
+const VERTEX_SHADER: &str = "vs.wgsl";
+
+/// Errors found so far; the build fails once every shader has been checked.
+#[derive(Default)]
+struct Diagnostics {
+    errors: usize,
+}
+
+impl Diagnostics {
+    /// Reports `message` about `path` at `line`, with naga's `report` on stderr.
+    fn error(&mut self, path: &Path, line: usize, message: &str, report: Option<&str>) {
+        println!("cargo::error={}:{}: {}", path.display(), line, message);
+        if let Some(report) = report {
+            eprintln!("{}", report);
+        }
+        self.errors += 1;
+    }
+
+    /// Reports an error in `expanded` at the file and line it came from, or
+    /// at `path` if it's in the code generated after it.
+    fn validation(&mut self, path: &Path, expanded: &preprocess::Expanded, error: validate::Error) {
+        match expanded.origin(error.line) {
+            Some(origin) => self.error(&Path::new(SHADER_DIR).join(&origin.file), origin.line, &error.message, error.report.as_deref()),
+            None => self.error(path, 1, &format!("in generated code: {}", error.message), error.report.as_deref()),
+        }
+    }
+}
+
+/// Expands the directives in `name`, a path under `SHADER_DIR`.
+fn expand(name: &str, diagnostics: &mut Diagnostics) -> Option<preprocess::Expanded> {
+    let mut load = |file: &str| fs::read_to_string(Path::new(SHADER_DIR).join(file)).map_err(|e| e.to_string());
+    preprocess::expand(name, &mut load)
+        .map_err(|e| diagnostics.error(&Path::new(SHADER_DIR).join(&e.file), e.line, &e.message, None))
+        .ok()
+}
+
+/// The `.wgsl` files directly in `dir`, sorted.
+fn wgsl_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "wgsl"))
+        .collect();
+    paths.sort();
+    paths
+}
 
 fn main() {
-    let out_dir = env::var_os("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join("shaders.rs");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let dest_path = out_dir.join("shaders.rs");
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let mut diagnostics = Diagnostics::default();
+    fs::create_dir_all(out_dir.join("shaders")).unwrap();
+
+    // The uniforms on their own, so a bad table is reported once, not per
+    // shader. Their WGSL is all generated, so errors point at the table.
+    let layout = Path::new("src/shader/uniforms.rs");
+    match validate::module(&layout.to_string_lossy(), &uniforms::wgsl()) {
+        Ok(module) => {
+            for message in validate::layout(&module) {
+                diagnostics.error(layout, 1, &message, None);
+            }
+        }
+        Err(e) => diagnostics.error(layout, 1, &e.message, e.report.as_deref()),
+    }
+
+    // The renderer's transition pass isn't a background shader, but it's
+    // compiled at runtime all the same.
+    let composite = Path::new("src/renderer/composite.wgsl");
+    if let Err(e) = validate::module(&composite.to_string_lossy(), &fs::read_to_string(composite).unwrap()) {
+        diagnostics.error(composite, e.line, &e.message, e.report.as_deref());
+    }
 
     let mut shader_code = String::new();
-    shader_code.push_str("static SHADER_SOURCES: &[(&str, &str)] = &[
-");
-
-    for entry in fs::read_dir("src/shaders").unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-        if path.is_file() {
-            if let Some(file_name_osstr) = path.file_name() {
-                if let Some(file_name) = file_name_osstr.to_str() {
-                    if file_name.ends_with(".wgsl") {
-                        println!("cargo:rerun-if-changed={}", path.display());
-                        let content = fs::read_to_string(&path).unwrap();
-                        // Use the filename without extension as the shader name
-                        let shader_name = file_name.trim_end_matches(".wgsl");
-                        shader_code.push_str(&format!(
-                            "    (\"{}\", r#\"{}\"#),\n",
-                            shader_name,
-                            content
-                        ));
-                    }
+    let vs_path = Path::new(SHADER_DIR).join(VERTEX_SHADER);
+    let vertex = expand(VERTEX_SHADER, &mut diagnostics).and_then(|expanded| {
+        validate::module(&vs_path.to_string_lossy(), &expanded.text).map_err(|e| diagnostics.validation(&vs_path, &expanded, e)).ok()
+    });
+    writeln!(shader_code, "pub static VERTEX_SHADER: &str = include_str!({:?});", manifest_dir.join(&vs_path)).unwrap();
+
+    shader_code.push_str("pub static LIBRARY: &[(&str, &str)] = &[\n");
+    for path in wgsl_files(&Path::new(SHADER_DIR).join(LIBRARY_DIR)) {
+        let name = format!("{}/{}", LIBRARY_DIR, path.file_name().unwrap().to_str().unwrap());
+        writeln!(shader_code, "    ({:?}, include_str!({:?})),", name, manifest_dir.join(&path)).unwrap();
+    }
+    shader_code.push_str("];\n");
+
+    shader_code.push_str("pub static SHADERS: &[ShaderInfo] = &[\n");
+    let paths = wgsl_files(Path::new(SHADER_DIR));
+    let mut default = None;
+    for path in paths.iter().filter(|path| **path != vs_path) {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let content = fs::read_to_string(path).unwrap();
+        // The file name without extension is the shader's id
+        let id = path.file_stem().unwrap().to_str().unwrap();
+        if let Err(message) = header::check_id(id) {
+            diagnostics.error(path, 1, &message, None);
+            continue;
+        }
+        let header = match header::parse(&content) {
+            Ok(header) => header,
+            Err(e) => {
+                diagnostics.error(path, e.line, &e.message, None);
+                continue;
+            }
+        };
+        if header.default {
+            if let Some(other) = default.replace(id) {
+                diagnostics.error(path, 1, &format!("only one shader can be the @default, and {}.wgsl already is", other), None);
+            }
+        }
+        let Some(expanded) = expand(path.file_name().unwrap().to_str().unwrap(), &mut diagnostics) else {
+            continue;
+        };
+
+        // Check what the renderer will compile: the expanded file plus the generated code.
+        let full = format!("{}\n{}", expanded.text, header::generated(&header.params, &header.passes));
+        // naga's report shows the expanded source, so it mustn't look like the file.
+        match validate::module(&format!("{} (expanded)", path.display()), &full) {
+            Ok(module) => {
+                let interface = graph::entries(&header.passes)
+                    .iter()
+                    .flat_map(|pass| vertex.as_ref().map(|vertex| validate::interface(vertex, &module, &full, &pass.name)).unwrap_or_default())
+                    .collect::<Vec<_>>();
+                let checks = interface.into_iter().chain(validate::resources(&module, &full)).chain(validate::inputs(&module, &header.passes, &full));
+                for error in checks {
+                    diagnostics.validation(path, &expanded, error);
                 }
             }
+            Err(e) => diagnostics.validation(path, &expanded, e),
         }
+        let expanded_path = out_dir.join("shaders").join(format!("{}.wgsl", id));
+        fs::write(&expanded_path, &expanded.text).unwrap();
+
+        let params: String = header
+            .params
+            .iter()
+            .map(|p| {
+                format!(
+                    "Param {{ name: Cow::Borrowed({:?}), default: {:?}, min: {:?}, max: {:?}, description: Cow::Borrowed({:?}) }}, ",
+                    p.name, p.default, p.min, p.max, p.description
+                )
+            })
+            .collect();
+        let tags: String = header.tags.iter().map(|tag| format!("Cow::Borrowed({:?}), ", tag)).collect();
+        let passes: String = header
+            .passes
+            .iter()
+            .map(|pass| {
+                let inputs: String = pass
+                    .inputs
+                    .iter()
+                    .map(|input| match input {
+                        graph::Input::Pass(name) => format!("Input::Pass(Cow::Borrowed({:?})), ", name),
+                        graph::Input::Previous(name) => format!("Input::Previous(Cow::Borrowed({:?})), ", name),
+                        graph::Input::Noise => "Input::Noise, ".to_string(),
+                    })
+                    .collect();
+                format!("Pass {{ name: Cow::Borrowed({:?}), scale: {:?}, inputs: Cow::Borrowed(&[{}]) }}, ", pass.name, pass.scale, inputs)
+            })
+            .collect();
+        writeln!(
+            shader_code,
+            "    ShaderInfo {{ id: Cow::Borrowed({:?}), name: Cow::Borrowed({:?}), description: Cow::Borrowed({:?}), author: Cow::Borrowed({:?}), tags: Cow::Borrowed(&[{}]), hidden: {}, default: {}, params: Cow::Borrowed(&[{}]), passes: Cow::Borrowed(&[{}]), source: Cow::Borrowed(include_str!({:?})), expanded: Cow::Borrowed(include_str!({:?})) }},",
+            id,
+            header.name.as_deref().unwrap_or(id),
+            header.description,
+            header.author,
+            tags,
+            header.hidden,
+            header.default,
+            params,
+            passes,
+            manifest_dir.join(path),
+            expanded_path
+        )
+        .unwrap();
+    }
+    if default.is_none() {
+        diagnostics.error(Path::new(SHADER_DIR), 1, "no shader is marked @default", None);
+    }
+    if diagnostics.errors > 0 {
+        process::exit(1);
     }
 
     shader_code.push_str("];\n");
 
     fs::write(&dest_path, shader_code).unwrap();
+    println!("cargo:rerun-if-changed={}", vs_path.display());
     println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rerun-if-changed=src/shaders");
-}
\ No newline at end of file
+    println!("cargo:rerun-if-changed={}", composite.display());
+    println!("cargo:rerun-if-changed=src/shader/validate.rs");
+    println!("cargo:rerun-if-changed=src/shader/graph.rs");
+    println!("cargo:rerun-if-changed=src/shader/header.rs");
+    println!("cargo:rerun-if-changed=src/shader/preprocess.rs");
+    println!("cargo:rerun-if-changed=src/shader/uniforms.rs");
+    println!("cargo:rerun-if-changed={}", SHADER_DIR);
+}
@@ -24,6 +24,8 @@ echo "📦 Copying files to www directory..."
 ls -l pkg
 mkdir -p www/pkg
 cp -r pkg/. www/pkg/
+# Served for the shader watcher, which reloads edits without a rebuild
+ln -sfn ../src/shaders www/shaders
 
 # Find an available port
 PORT=8000
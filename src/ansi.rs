@@ -0,0 +1,94 @@
+//! ANSI escape sequences for the terminal: SGR colors and styles, and OSC 8
+//! hyperlinks, which xterm.js renders as clickable links.
+
+use unicode_width::UnicodeWidthStr;
+
+pub const BOLD: &str = "1";
+pub const DIM: &str = "2";
+pub const UNDERLINE: &str = "4";
+pub const RED: &str = "31";
+pub const GREEN: &str = "32";
+pub const YELLOW: &str = "33";
+pub const BLUE: &str = "34";
+pub const MAGENTA: &str = "35";
+pub const CYAN: &str = "36";
+
+/// `text` drawn with every SGR code in `styles`, e.g. `paint(&[BOLD, BLUE], "dir/")`.
+pub fn paint(styles: &[&str], text: &str) -> String {
+    format!("\x1b[{}m{}\x1b[0m", styles.join(";"), text)
+}
+
+/// `text` as a link to `url`.
+pub fn link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// `text` without its escape sequences.
+pub fn strip(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            plain.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte in `@`..=`~`.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: up to BEL or the `ESC \` string terminator.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next() == Some('\\')) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    plain
+}
+
+/// How many columns `text` takes up on screen: two for wide characters like
+/// CJK, none for combining marks.
+pub fn width(text: &str) -> usize {
+    if text.contains('\x1b') {
+        strip(text).width()
+    } else {
+        text.width()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_sets_and_resets_styles() {
+        assert_eq!(paint(&[BOLD, BLUE], "dir/"), "\x1b[1;34mdir/\x1b[0m");
+    }
+
+    #[test]
+    fn strip_removes_styles_and_links() {
+        assert_eq!(strip(&paint(&[BOLD, BLUE], "dir/")), "dir/");
+        assert_eq!(strip(&format!("see {}.", link("https://example.com", &paint(&[UNDERLINE], "site")))), "see site.");
+        assert_eq!(strip("\x1b]8;;https://example.com\x07bel\x1b]8;;\x07"), "bel");
+        assert_eq!(strip("no escapes"), "no escapes");
+    }
+
+    #[test]
+    fn width_counts_columns() {
+        assert_eq!(width("plain"), 5);
+        assert_eq!(width(&paint(&[RED], "red")), 3);
+        assert_eq!(width(&link("https://example.com", "site")), 4);
+        assert_eq!(width("日本語"), 6);
+        assert_eq!(width(&paint(&[BOLD], "日本")), 4);
+        assert_eq!(width("e\u{301}"), 1);
+    }
+}
@@ -0,0 +1,446 @@
+//! Terminal commands: the [`Command`] trait, the [`Registry`] the shell
+//! dispatches through, and what a command line hands back to the page.
+//!
+//! A command line produces text for the terminal plus any number of [`Effect`]s
+//! for `index.js` to carry out. Both cross into JS through serde-wasm-bindgen,
+//! so effects arrive as plain objects tagged by `type`.
+
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use crate::shell::{Output, Terminal};
+
+/// The result of running one command line.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandResult {
+    /// Text to print, with `\n` line endings.
+    pub output: String,
+    /// Side effects to apply, in order, after `output` has been printed. A
+    /// [`Effect::Clear`] only ever comes first, and is applied before printing.
+    pub effects: Vec<Effect>,
+}
+
+/// Something the page should do besides printing text.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Effect {
+    /// Switch the background to the named shader with a `transition` (a
+    /// `shader::transition::Kind` name) lasting `duration_ms`.
+    SetShader { name: String, transition: String, duration_ms: u32 },
+    /// Open the shader editor on `source`; saving loads it as shader `name`.
+    EditShader { name: String, source: String },
+    /// Clear the terminal.
+    Clear,
+    /// Offer `bytes` to the user as a download named `filename`.
+    DownloadFile {
+        filename: String,
+        mime: String,
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+    /// Open `url` in a new tab.
+    OpenUrl { url: String },
+    /// Scroll the resume to a section (`experience`, `skills`, ...), or to the top when `None`.
+    ScrollToSection { section: Option<String> },
+    /// Run `code` with Pyodide and print the result.
+    RunPython { code: String },
+    /// Show `text` a screenful at a time, like `more`.
+    More { text: String },
+}
+
+impl CommandResult {
+    /// A result that only prints `output`.
+    pub fn text(output: impl Into<String>) -> Self {
+        CommandResult { output: output.into(), effects: Vec::new() }
+    }
+}
+
+/// The future returned by [`Command::run`]. Commands run on the page's single
+/// thread, so it need not be `Send`.
+pub type RunFuture<'a> = Pin<Box<dyn Future<Output = Output> + 'a>>;
+
+/// The flag every command accepts to turn off colors and links.
+pub const NO_COLOR: &str = "--no-color";
+
+/// What a command gets besides its arguments.
+pub struct Context<'a> {
+    /// The registry the command was found in, for commands like `help`.
+    pub registry: &'a Registry,
+    /// The previous command's stdout inside a pipeline.
+    pub stdin: Option<&'a str>,
+    /// Where stdout is shown, or `None` when it's piped or redirected.
+    pub terminal: Option<Terminal>,
+}
+
+impl Context<'_> {
+    /// Whether stdout may be styled with escape sequences.
+    pub fn color(&self) -> bool {
+        self.terminal.is_some_and(|terminal| terminal.color)
+    }
+}
+
+/// One argument in a command's spec, used for `help <cmd>` and completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arg {
+    /// `--format`, `-n`, or a placeholder like `PATTERN` for positionals.
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub help: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A switch that takes no value.
+    Flag,
+    /// A flag followed by a value.
+    Option(Value),
+    /// A bare argument.
+    Positional(Value),
+    /// A word that can stand in for the first positional, after which the
+    /// command takes these arguments instead, like `shader set PARAM VALUE`.
+    Subcommand(&'static [Arg]),
+}
+
+/// What kind of value an argument takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    /// Free text.
+    Text,
+    /// One of a fixed set of words.
+    OneOf(&'static [&'static str]),
+    /// A shader name.
+    Shader,
+    /// A parameter of the active shader.
+    Param,
+    /// A resume section key.
+    Section,
+    /// A file path.
+    Path,
+    /// The name of another command.
+    Command,
+}
+
+/// A terminal command.
+pub trait Command {
+    fn name(&self) -> &'static str;
+
+    /// Other names the command answers to.
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The synopsis shown by `help <cmd>` and on bad arguments, e.g. `grep PATTERN`.
+    fn usage(&self) -> &'static str;
+
+    /// One line for `help`.
+    fn description(&self) -> &'static str;
+
+    fn args(&self) -> &'static [Arg] {
+        &[]
+    }
+
+    /// Whether the command takes the rest of its line as one argument, just as
+    /// typed: no quoting, variables, pipes, `;` or redirects. For code in
+    /// another language, like `python`'s.
+    fn raw(&self) -> bool {
+        false
+    }
+
+    /// Runs the command with `args` (without the command name itself).
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a>;
+}
+
+/// The standard "bad arguments" failure for `command`.
+pub fn usage_error(command: &dyn Command) -> Output {
+    Output::error(format!("usage: {}", command.usage()))
+}
+
+/// The commands a shell can run, looked up by name or alias.
+#[derive(Clone, Default)]
+pub struct Registry {
+    commands: Vec<Rc<dyn Command>>,
+}
+
+impl Registry {
+    /// A registry holding only `help`.
+    pub fn new() -> Self {
+        let mut registry = Registry::default();
+        registry.register(Help);
+        registry
+    }
+
+    /// Adds `command`, replacing any existing command with the same name.
+    pub fn register(&mut self, command: impl Command + 'static) {
+        self.commands.retain(|c| c.name() != command.name());
+        self.commands.push(Rc::new(command));
+        self.commands.sort_by_key(|c| c.name());
+    }
+
+    /// Finds a command by name or alias.
+    pub fn get(&self, name: &str) -> Option<&Rc<dyn Command>> {
+        self.commands.iter().find(|c| c.name() == name || c.aliases().contains(&name))
+    }
+
+    /// All commands, sorted by name.
+    pub fn commands(&self) -> impl Iterator<Item = &dyn Command> {
+        self.commands.iter().map(|c| c.as_ref())
+    }
+
+    /// Names and aliases close enough to `name` to be what the user meant.
+    pub fn suggest(&self, name: &str) -> Vec<&'static str> {
+        let limit = if name.chars().count() <= 3 { 1 } else { 2 };
+        let mut matches: Vec<(usize, &'static str)> = self
+            .commands
+            .iter()
+            .flat_map(|c| std::iter::once(c.name()).chain(c.aliases().iter().copied()))
+            .map(|candidate| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= limit)
+            .collect();
+        matches.sort();
+        matches.into_iter().map(|(_, candidate)| candidate).collect()
+    }
+
+    /// Runs `argv[0]` with the rest of `argv`. Every command takes `--no-color`,
+    /// which is removed from the arguments before the command sees them; after
+    /// a `--` it's left alone, like any other argument.
+    pub async fn run(&self, argv: &[String], stdin: Option<&str>, terminal: Option<Terminal>) -> Output {
+        let Some((name, args)) = argv.split_first() else {
+            return Output::default();
+        };
+        let Some(command) = self.get(name) else {
+            let mut message = format!("{}: command not found", name);
+            let suggestions = self.suggest(name);
+            if !suggestions.is_empty() {
+                message.push_str(&format!("\ndid you mean: {}?", suggestions.join(", ")));
+            }
+            return Output { status: 127, ..Output::error(message) };
+        };
+        let options = args.iter().position(|arg| arg == "--").unwrap_or(args.len());
+        let no_color = args[..options].iter().any(|arg| arg == NO_COLOR);
+        let args: Vec<String> =
+            args.iter().enumerate().filter(|(i, arg)| *i >= options || *arg != NO_COLOR).map(|(_, arg)| arg.clone()).collect();
+        let terminal = terminal.map(|terminal| Terminal { color: terminal.color && !no_color, ..terminal });
+        command.run(Context { registry: self, stdin, terminal }, &args).await
+    }
+
+    /// The `help` overview: every command with its description.
+    pub fn help(&self) -> String {
+        let width = self.commands().map(|c| c.name().len()).max().unwrap_or(0);
+        let mut out = String::from("Available commands:\n");
+        for command in self.commands() {
+            out.push_str(&format!("  {:width$}  {}\n", command.name(), command.description(), width = width));
+        }
+        out.push_str("\nCommands can be piped (cat resume | grep Rust | wc -l), chained with ; and &&,\n");
+        out.push_str("and redirected with > or >>. Any command takes --no-color for plain output\n");
+        out.push_str("(write -- before an argument that is literally --no-color).\n");
+        out.push_str("Type 'help <command>' for details.");
+        out
+    }
+
+    /// `help <cmd>`: usage, aliases and arguments of one command.
+    pub fn help_for(&self, name: &str) -> Option<String> {
+        let command = self.get(name)?;
+        let mut out = format!("usage: {}\n{}", command.usage(), command.description());
+        if !command.aliases().is_empty() {
+            out.push_str(&format!("\naliases: {}", command.aliases().join(", ")));
+        }
+        let args = command.args();
+        if !args.is_empty() {
+            // A subcommand is shown with the arguments it takes.
+            let label = |arg: &Arg| match arg.kind {
+                ArgKind::Subcommand(args) => std::iter::once(arg.name).chain(args.iter().map(|a| a.name)).collect::<Vec<&str>>().join(" "),
+                _ => arg.name.to_string(),
+            };
+            let width = args.iter().map(|a| label(a).len()).max().unwrap_or(0);
+            out.push_str("\n\narguments:");
+            for arg in args {
+                let choices = match arg.kind {
+                    ArgKind::Option(Value::OneOf(words)) | ArgKind::Positional(Value::OneOf(words)) => {
+                        format!(" ({})", words.join("|"))
+                    }
+                    _ => String::new(),
+                };
+                out.push_str(&format!("\n  {:width$}  {}{}", label(arg), arg.help, choices, width = width));
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Levenshtein distance, for "did you mean".
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// `help [COMMAND]`, generated from the registry.
+struct Help;
+
+impl Command for Help {
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn usage(&self) -> &'static str {
+        "help [COMMAND]"
+    }
+
+    fn description(&self) -> &'static str {
+        "List commands, or show how to use one"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "COMMAND", kind: ArgKind::Positional(Value::Command), help: "command to describe" }]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            match args {
+                [] => ctx.registry.help().into(),
+                [name] => match ctx.registry.help_for(name) {
+                    Some(help) => help.into(),
+                    None => Output::error(format!("help: no such command '{}'", name)),
+                },
+                _ => usage_error(self),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::block_on;
+
+    /// Prints its arguments, one per line, after whether it may use color.
+    struct Shader;
+
+    impl Command for Shader {
+        fn name(&self) -> &'static str {
+            "shader"
+        }
+
+        fn aliases(&self) -> &'static [&'static str] {
+            &["sh"]
+        }
+
+        fn usage(&self) -> &'static str {
+            "shader NAME | shader set PARAM VALUE"
+        }
+
+        fn description(&self) -> &'static str {
+            "Switch the background"
+        }
+
+        fn args(&self) -> &'static [Arg] {
+            &[
+                Arg { name: "NAME", kind: ArgKind::Positional(Value::Shader), help: "shader to show" },
+                Arg { name: "--transition", kind: ArgKind::Option(Value::OneOf(&["fade", "cut"])), help: "how to switch" },
+                Arg {
+                    name: "set",
+                    kind: ArgKind::Subcommand(&[
+                        Arg { name: "PARAM", kind: ArgKind::Positional(Value::Param), help: "" },
+                        Arg { name: "VALUE", kind: ArgKind::Positional(Value::Text), help: "" },
+                    ]),
+                    help: "change a parameter",
+                },
+            ]
+        }
+
+        fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+            Box::pin(async move { std::iter::once(ctx.color().to_string()).chain(args.iter().cloned()).collect::<Vec<String>>().join("\n").into() })
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(Shader);
+        registry
+    }
+
+    fn run(line: &str) -> Output {
+        let argv: Vec<String> = line.split(' ').map(str::to_string).collect();
+        block_on(registry().run(&argv, None, Some(Terminal::default())))
+    }
+
+    #[test]
+    fn measures_edit_distance() {
+        let cases = [("", "", 0), ("", "abc", 3), ("cat", "cat", 0), ("cta", "cat", 2), ("kitten", "sitting", 3), ("shadr", "shader", 1)];
+        for (a, b, expected) in cases {
+            assert_eq!(edit_distance(a, b), expected, "{} {}", a, b);
+            assert_eq!(edit_distance(b, a), expected, "{} {}", b, a);
+        }
+    }
+
+    #[test]
+    fn suggests_one_edit_for_short_names_and_two_for_longer() {
+        let registry = registry();
+        let cases: [(&str, &[&str]); 8] = [
+            ("hlp", &["help"]),
+            ("s", &["sh"]),
+            ("sx", &["sh"]),
+            ("xyz", &[]),
+            ("shadr", &["shader"]),
+            ("shdr", &["sh", "shader"]),
+            ("shaderz", &["shader"]),
+            ("hxlpxx", &[]),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(registry.suggest(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn unknown_commands_fail_with_127_and_suggestions() {
+        let output = run("shadr");
+        assert_eq!(output.status, 127);
+        assert_eq!(output.stderr, "shadr: command not found\ndid you mean: shader?");
+        assert_eq!(run("zzzzzz").stderr, "zzzzzz: command not found");
+        assert_eq!(block_on(registry().run(&[], None, None)), Output::default());
+    }
+
+    #[test]
+    fn strips_no_color_only_before_a_double_dash() {
+        assert_eq!(run("sh fire").stdout, "true\nfire");
+        assert_eq!(run("sh --no-color fire").stdout, "false\nfire");
+        assert_eq!(run("sh fire --no-color").stdout, "false\nfire");
+        assert_eq!(run("sh -- --no-color").stdout, "true\n--\n--no-color");
+        assert_eq!(run("sh --no-color -- --no-color").stdout, "false\n--\n--no-color");
+    }
+
+    #[test]
+    fn help_lists_every_command() {
+        let help = registry().help();
+        assert!(help.starts_with("Available commands:\n  help    List commands, or show how to use one\n  shader  Switch the background\n"), "{}", help);
+        assert_eq!(run("help").stdout, help);
+    }
+
+    #[test]
+    fn help_for_shows_aliases_subcommands_and_choices() {
+        let expected = "usage: shader NAME | shader set PARAM VALUE
+Switch the background
+aliases: sh
+
+arguments:
+  NAME             shader to show
+  --transition     how to switch (fade|cut)
+  set PARAM VALUE  change a parameter";
+        assert_eq!(registry().help_for("sh").as_deref(), Some(expected));
+        assert_eq!(run("help shader").stdout, expected);
+        assert_eq!(registry().help_for("help").as_deref(), Some("usage: help [COMMAND]\nList commands, or show how to use one\n\narguments:\n  COMMAND  command to describe"));
+        assert_eq!(run("help nope").stderr, "help: no such command 'nope'");
+        assert_eq!(registry().help_for("nope"), None);
+    }
+}
@@ -0,0 +1,30 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::gallery::{self, GalleryItem};
+use crate::resume::Resume;
+
+pub async fn fetch_text(url: &str) -> Result<String, JsValue> {
+    let opts = web_sys::RequestInit::new();
+    opts.set_method("GET");
+    opts.set_mode(web_sys::RequestMode::Cors);
+    let request = web_sys::Request::new_with_str_and_init(url, &opts)?;
+    let window = web_sys::window().unwrap();
+    let resp_value = wasm_bindgen_futures::JsFuture::from(window.fetch_with_request(&request)).await?;
+    let resp: web_sys::Response = resp_value.dyn_into().unwrap();
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!("{}: HTTP {}", url, resp.status())));
+    }
+    let text = wasm_bindgen_futures::JsFuture::from(resp.text()?).await?;
+    Ok(text.as_string().unwrap_or_default())
+}
+
+pub async fn fetch_resume() -> Result<Resume, JsValue> {
+    let json = fetch_text("./resume.json").await?;
+    Resume::from_json_str(&json).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+pub async fn fetch_gallery() -> Result<Vec<GalleryItem>, JsValue> {
+    let json = fetch_text("./gallery.json").await?;
+    gallery::from_json_str(&json).map_err(|e| JsValue::from_str(&e.to_string()))
+}
@@ -0,0 +1,26 @@
+//! The media gallery described by `gallery.json`.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GalleryItem {
+    #[serde(rename = "type")]
+    pub kind: MediaKind,
+    /// Path relative to the site root, e.g. `gallery_media/hybrid-gs.png`.
+    pub src: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Parses the contents of `gallery.json`.
+pub fn from_json_str(json: &str) -> Result<Vec<GalleryItem>, serde_json::Error> {
+    serde_json::from_str(json)
+}
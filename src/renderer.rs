@@ -1,26 +1,41 @@
+mod graph;
+
 use web_sys::HtmlCanvasElement;
 use wgpu::util::DeviceExt;
 
-include!(concat!(env!("OUT_DIR"), "/shaders.rs"));
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::shader::load::{self, Diagnostic};
+use crate::shader::transition::Transition;
+use crate::shader::{self, uniforms, Params, ShaderInfo};
 
+use graph::{Graph, Shared};
+
+/// What every shader reads as `uniforms`, laid out as `shader::uniforms`
+/// says; build.rs checks that layout against naga's.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 struct Uniforms {
     time: f32,
-    _pad0: [u32; 3],
-    _padding1: [f32; 3],
-    _pad1: [u32; 1],
+    _pad0: f32,
     mouse: [f32; 2],
-    _padding2: [f32; 2],
     resolution: [f32; 2],
-    _padding3: [f32; 2],
-    _extra_padding1: [f32; 4],
-    _extra_padding2: [f32; 4],
-    _extra_padding3: [f32; 4],
-    _extra_padding4: [f32; 4],
-    _extra_padding5: [f32; 4],
+    _pad1: [f32; 2],
+    /// The active shader's parameters, packed by `Params::packed`.
+    params: [[f32; 4]; uniforms::PARAM_VEC4S],
 }
 
+const _: () = {
+    use std::mem::{offset_of, size_of};
+    assert!(offset_of!(Uniforms, time) == uniforms::offset("time"));
+    assert!(offset_of!(Uniforms, mouse) == uniforms::offset("mouse"));
+    assert!(offset_of!(Uniforms, resolution) == uniforms::offset("resolution"));
+    assert!(offset_of!(Uniforms, params) == uniforms::offset("params"));
+    assert!(size_of::<Uniforms>() == uniforms::SIZE as usize);
+};
+
 use std::collections::HashMap;
 
 pub struct State {
@@ -29,15 +44,65 @@ pub struct State {
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
     pub size: (u32, u32),
-    render_pipelines: HashMap<String, wgpu::RenderPipeline>,
+    /// Every shader's passes, by shader name.
+    graphs: HashMap<String, Graph>,
+    /// Every shader's parameters, by shader name.
+    params: HashMap<String, Params>,
+    /// The shaders with a pipeline, embedded ones first.
+    shaders: Vec<ShaderInfo>,
+    shared: Shared,
     active_pipeline: String,
     uniforms: Uniforms,
     uniform_buffer: wgpu::Buffer,
     uniform_bind_group: wgpu::BindGroup,
+    outgoing_uniform_buffer: wgpu::Buffer,
+    outgoing_bind_group: wgpu::BindGroup,
+    composite: Composite,
+    transition: Option<Running>,
+    /// Embedded shaders the browser wouldn't compile.
+    failed_shaders: Vec<Diagnostic>,
+}
+
+/// Why there's no renderer; the page falls back to a static background.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RendererError {
+    /// The canvas can't be drawn to with WebGPU or WebGL2.
+    Surface(String),
+    /// No GPU adapter can draw to the canvas.
+    NoAdapter,
+    Device(String),
+    /// The adapter supports no format for the canvas.
+    UnsupportedSurface,
+    /// Every shader failed to compile.
+    NoShaders(Vec<Diagnostic>),
+}
+
+impl fmt::Display for RendererError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RendererError::Surface(e) => write!(f, "can't create a WebGPU or WebGL2 surface: {}", e),
+            RendererError::NoAdapter => write!(f, "no WebGPU or WebGL2 adapter is available"),
+            RendererError::Device(e) => write!(f, "can't open the GPU device: {}", e),
+            RendererError::UnsupportedSurface => write!(f, "the GPU can't draw to the canvas"),
+            RendererError::NoShaders(failed) => {
+                write!(f, "no shader compiled")?;
+                for diagnostic in failed {
+                    write!(f, "\n  {}", diagnostic)?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
+impl std::error::Error for RendererError {}
+
 impl State {
-    pub async fn new(canvas: HtmlCanvasElement) -> Self {
+    /// Starts on `default_shader` if there is one by that name, otherwise on
+    /// the shader marked `@default`.
+    /// A shader that fails to compile is left out and reported by
+    /// [`State::failed_shaders`]; it's an error only if none compile.
+    pub async fn new(canvas: HtmlCanvasElement, default_shader: Option<&str>) -> Result<Self, RendererError> {
         let size = (canvas.width(), canvas.height());
         web_sys::console::log_2(&"Canvas size:".into(), &format!("{}x{}", size.0, size.1).into());
 
@@ -46,7 +111,9 @@ impl State {
             ..Default::default()
         });
 
-        let surface = instance.create_surface(wgpu::SurfaceTarget::Canvas(canvas)).unwrap();
+        let surface = instance
+            .create_surface(wgpu::SurfaceTarget::Canvas(canvas))
+            .map_err(|e| RendererError::Surface(e.to_string()))?;
 
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
@@ -55,7 +122,7 @@ impl State {
                 force_fallback_adapter: false,
             })
             .await
-            .unwrap();
+            .ok_or(RendererError::NoAdapter)?;
 
         let (device, queue) = adapter
             .request_device(
@@ -67,7 +134,11 @@ impl State {
                 None,
             )
             .await
-            .unwrap();
+            .map_err(|e| RendererError::Device(e.to_string()))?;
+
+        // wgpu panics on errors outside an error scope by default, which would
+        // take the whole renderer down; shaders compile inside one anyway.
+        device.on_uncaptured_error(Box::new(|error| web_sys::console::error_1(&error.to_string().into())));
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -75,7 +146,8 @@ impl State {
             .iter()
             .copied()
             .find(|f| f.is_srgb())
-            .unwrap_or(surface_caps.formats[0]);
+            .or(surface_caps.formats.first().copied())
+            .ok_or(RendererError::UnsupportedSurface)?;
 
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -93,16 +165,9 @@ impl State {
             time: 0.0,
             mouse: [0.0, 0.0],
             resolution: [size.0 as f32, size.1 as f32],
-            _pad0: [0; 3],
-            _padding1: [0.0; 3],
-            _pad1: [0; 1],
-            _padding2: [0.0; 2],
-            _padding3: [0.0; 2],
-            _extra_padding1: [0.0; 4],
-            _extra_padding2: [0.0; 4],
-            _extra_padding3: [0.0; 4],
-            _extra_padding4: [0.0; 4],
-            _extra_padding5: [0.0; 4],
+            _pad0: 0.0,
+            _pad1: [0.0; 2],
+            params: [[0.0; 4]; uniforms::PARAM_VEC4S],
         };
 
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -111,23 +176,10 @@ impl State {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-        let uniform_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-                label: Some("uniform_bind_group_layout"),
-            });
+        let shared = Shared::new(&device, &queue, config.format);
 
         let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &uniform_bind_group_layout,
+            layout: &shared.uniform_layout,
             entries: &[wgpu::BindGroupEntry {
                 binding: 0,
                 resource: uniform_buffer.as_entire_binding(),
@@ -135,87 +187,98 @@ impl State {
             label: Some("uniform_bind_group"),
         });
 
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&uniform_bind_group_layout],
-                push_constant_ranges: &[],
-            });
-
-        let mut render_pipelines = HashMap::new();
-
-        let vs_source = SHADER_SOURCES
-            .iter()
-            .find(|(name, _)| *name == "vs")
-            .map(|(_, source)| *source)
-            .expect("vs.wgsl not found");
+        // The outgoing shader's uniforms during a transition, which has its own parameters.
+        let outgoing_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Outgoing Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
 
-        let vs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Vertex Shader"),
-            source: wgpu::ShaderSource::Wgsl(vs_source.into()),
+        let outgoing_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &shared.uniform_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: outgoing_uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("outgoing_bind_group"),
         });
 
-        for (name, source) in SHADER_SOURCES.iter().filter(|(name, _)| *name != "vs") {
-            let fs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-                label: Some(&format!("{} Fragment Shader", name)),
-                source: wgpu::ShaderSource::Wgsl((*source).into()),
-            });
+        let mut graphs = HashMap::new();
+        let mut params = HashMap::new();
+        let mut shaders = Vec::new();
+        let mut failed_shaders = Vec::new();
+
+        let composite = Composite::new(&device, &shared.vs_module, config.format, size);
+
+        for info in shader::SHADERS {
+            let wgsl = info.wgsl();
+            device.push_error_scope(wgpu::ErrorFilter::Validation);
+            let graph = Graph::new(&device, &shared, &info.id, &wgsl, &info.passes);
+            if let Some(error) = device.pop_error_scope().await {
+                // naga passed it at build time, so this is the browser's compiler disagreeing.
+                let diagnostic = Diagnostic::new(&format!("{}.wgsl", info.id), None, error.to_string());
+                web_sys::console::error_2(&"Skipping a shader that failed to compile:".into(), &diagnostic.to_string().into());
+                failed_shaders.push(diagnostic);
+                continue;
+            }
+            graphs.insert(info.id.to_string(), graph);
+            params.insert(info.id.to_string(), Params::new(&info.params));
+            shaders.push(info.clone());
+        }
 
-            let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some(&format!("{} Render Pipeline", name)),
-                layout: Some(&render_pipeline_layout),
-                vertex: wgpu::VertexState {
-                    module: &vs_module,
-                    entry_point: "main",
-                    compilation_options: wgpu::PipelineCompilationOptions::default(),
-                    buffers: &[],
-                },
-                fragment: Some(wgpu::FragmentState {
-                    module: &fs_module,
-                    entry_point: "main",
-                    compilation_options: wgpu::PipelineCompilationOptions::default(),
-                    targets: &[Some(wgpu::ColorTargetState {
-                        format: config.format,
-                        blend: Some(wgpu::BlendState::REPLACE),
-                        write_mask: wgpu::ColorWrites::ALL,
-                    })],
-                }),
-                primitive: wgpu::PrimitiveState {
-                    topology: wgpu::PrimitiveTopology::TriangleList,
-                    ..Default::default()
-                },
-                depth_stencil: None,
-                multisample: wgpu::MultisampleState::default(),
-                multiview: None,
-            });
-            render_pipelines.insert(name.to_string(), pipeline);
+        if graphs.is_empty() {
+            return Err(RendererError::NoShaders(failed_shaders));
         }
 
-        let mut shader_names: Vec<String> = render_pipelines.keys().cloned().collect();
+        let mut shader_names: Vec<String> = graphs.keys().cloned().collect();
         shader_names.sort();
-        let active_pipeline = shader_names.first().cloned().unwrap_or_default();
+        let fallback = shader::default_shader()
+            .map(|info| info.id.to_string())
+            .filter(|id| graphs.contains_key(id))
+            .or_else(|| shader_names.first().cloned())
+            .unwrap_or_default();
+        let active_pipeline = match default_shader {
+            Some(name) if graphs.contains_key(name) => name.to_string(),
+            Some(name) => {
+                web_sys::console::warn_2(&"SHADER names an unknown shader:".into(), &name.into());
+                fallback
+            }
+            None => fallback,
+        };
         
         web_sys::console::log_2(&"Available shaders:".into(), &format!("{:?}", shader_names).into());
         web_sys::console::log_2(&"Default shader set to:".into(), &active_pipeline.clone().into());
 
-        Self {
+        Ok(Self {
             surface,
             device,
             queue,
             config,
             size,
-            render_pipelines,
+            graphs,
+            params,
+            shaders,
+            shared,
             active_pipeline,
             uniforms,
             uniform_buffer,
             uniform_bind_group,
-        }
+            outgoing_uniform_buffer,
+            outgoing_bind_group,
+            composite,
+            transition: None,
+            failed_shaders,
+        })
     }
 
-    pub fn set_pipeline(&mut self, name: &str) {
-        if self.render_pipelines.contains_key(name) {
-            self.active_pipeline = name.to_string();
+    /// Switches to shader `name`, animating the change unless `transition`
+    /// is instant. A transition still running is cut short.
+    pub fn set_pipeline(&mut self, name: &str, transition: Transition) {
+        if !self.graphs.contains_key(name) || name == self.active_pipeline {
+            return;
         }
+        let from = std::mem::replace(&mut self.active_pipeline, name.to_string());
+        self.transition = if transition.is_instant() { None } else { Some(Running { from, transition, start: self.uniforms.time }) };
     }
 
     pub fn resize(&mut self, new_size: (u32, u32)) {
@@ -225,13 +288,34 @@ impl State {
             self.config.height = new_size.1;
             self.surface.configure(&self.device, &self.config);
             self.uniforms.resolution = [new_size.0 as f32, new_size.1 as f32];
+            self.composite.resize(&self.device, new_size);
+            // The graphs reallocate their textures when they next draw.
         }
     }
 
     pub fn update(&mut self, time: f32, mouse: [f32; 2]) {
         self.uniforms.time = time;
         self.uniforms.mouse = mouse;
+        if let Some(params) = self.params.get(&self.active_pipeline) {
+            self.uniforms.params = params.packed();
+        }
         self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniforms]));
+
+        let Some(running) = &self.transition else {
+            return;
+        };
+        let progress = running.transition.progress(time - running.start);
+        if progress >= 1.0 {
+            self.transition = None;
+            return;
+        }
+        let mut outgoing = self.uniforms;
+        if let Some(params) = self.params.get(&running.from) {
+            outgoing.params = params.packed();
+        }
+        self.queue.write_buffer(&self.outgoing_uniform_buffer, 0, bytemuck::cast_slice(&[outgoing]));
+        let composite = CompositeUniforms { progress, mode: running.transition.kind.mode(), resolution: self.uniforms.resolution };
+        self.queue.write_buffer(&self.composite.uniform_buffer, 0, bytemuck::cast_slice(&[composite]));
     }
 
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
@@ -245,27 +329,27 @@ impl State {
                 label: Some("Render Encoder"),
             });
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
+        let from = self.transition.as_ref().map(|running| running.from.as_str());
+        for (name, graph) in self.graphs.iter_mut() {
+            if *name == self.active_pipeline || Some(name.as_str()) == from {
+                graph.begin_frame(&self.device, &self.shared, self.size);
+            }
+        }
 
-            if let Some(pipeline) = self.render_pipelines.get(&self.active_pipeline) {
-                render_pass.set_pipeline(pipeline);
-                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-                render_pass.draw(0..3, 0..1);
+        let active = self.graphs.get(&self.active_pipeline);
+        match from.and_then(|from| self.graphs.get(from)) {
+            Some(outgoing) => {
+                outgoing.render(&mut encoder, &self.composite.outgoing.view, &self.outgoing_bind_group);
+                match active {
+                    Some(active) => active.render(&mut encoder, &self.composite.incoming.view, &self.uniform_bind_group),
+                    None => draw(&mut encoder, &self.composite.incoming.view, None, &[]),
+                }
+                draw(&mut encoder, &view, Some(&self.composite.pipeline), &[&self.composite.bind_group]);
             }
+            None => match active {
+                Some(active) => active.render(&mut encoder, &view, &self.uniform_bind_group),
+                None => draw(&mut encoder, &view, None, &[]),
+            },
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
@@ -274,13 +358,286 @@ impl State {
         Ok(())
     }
 
+    /// The shaders that compiled and aren't `@hidden`, by id.
     pub fn get_shader_names(&self) -> Vec<String> {
-        let mut names: Vec<String> = self.render_pipelines.keys().cloned().collect();
-        names.sort();
-        names
+        self.shaders().filter(|info| !info.hidden).map(|info| info.id.to_string()).collect()
+    }
+
+    /// Every shader that compiled, hidden ones included.
+    pub fn shaders(&self) -> impl Iterator<Item = &ShaderInfo> {
+        self.shaders.iter()
+    }
+
+    /// Embedded shaders left out because they didn't compile.
+    pub fn failed_shaders(&self) -> &[Diagnostic] {
+        &self.failed_shaders
+    }
+
+    /// Checks `source` as shader `id` and starts creating its pipeline;
+    /// [`PendingShader::finish`] says whether the GPU accepted it.
+    pub fn compile_shader(&self, id: &str, source: &str, library: &dyn Fn(&str) -> Option<String>) -> Result<PendingShader, Vec<Diagnostic>> {
+        let prepared = load::prepare(id, source, library)?;
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let graph = Graph::new(&self.device, &self.shared, id, &prepared.wgsl, &prepared.info.passes);
+        let error = Box::pin(self.device.pop_error_scope());
+        Ok(PendingShader { prepared, graph, error })
+    }
+
+    /// Adds a compiled shader, or replaces the one with its id. Parameters
+    /// that are still declared keep their values.
+    pub fn install(&mut self, shader: CompiledShader) {
+        let CompiledShader { info, graph } = shader;
+        let id = info.id.to_string();
+        let mut params = Params::new(&info.params);
+        if let Some(old) = self.params.get(&id) {
+            for (param, value) in old.iter() {
+                let _ = params.set(&param.name, value);
+            }
+        }
+        let file = format!("{}.wgsl", id);
+        self.failed_shaders.retain(|diagnostic| diagnostic.file != file);
+        self.graphs.insert(id.clone(), graph);
+        self.params.insert(id.clone(), params);
+        match self.shaders.iter_mut().find(|s| s.id == id) {
+            Some(existing) => *existing = info,
+            None => self.shaders.push(info),
+        }
+    }
+
+    pub fn has_shader(&self, name: &str) -> bool {
+        self.graphs.contains_key(name)
     }
 
     pub fn get_active_shader(&self) -> String {
         self.active_pipeline.clone()
     }
+
+    /// The active shader's parameters.
+    pub fn params(&self) -> Option<&Params> {
+        self.params.get(&self.active_pipeline)
+    }
+
+    /// The active shader's parameters, to change; they take effect next frame.
+    pub fn params_mut(&mut self) -> Option<&mut Params> {
+        self.params.get_mut(&self.active_pipeline)
+    }
+}
+
+/// A shader [`State::compile_shader`] accepted, waiting on the GPU.
+pub struct PendingShader {
+    prepared: load::Prepared,
+    graph: Graph,
+    error: Pin<Box<dyn Future<Output = Option<wgpu::Error>>>>,
+}
+
+impl PendingShader {
+    /// Waits for wgpu's verdict. naga has already checked the source, so an
+    /// error here is one only the browser's compiler found.
+    pub async fn finish(self) -> Result<CompiledShader, Vec<Diagnostic>> {
+        match self.error.await {
+            Some(error) => Err(vec![self.prepared.gpu_error(&error.to_string())]),
+            None => Ok(CompiledShader { info: self.prepared.info, graph: self.graph }),
+        }
+    }
+}
+
+/// A shader ready for [`State::install`].
+pub struct CompiledShader {
+    info: ShaderInfo,
+    graph: Graph,
+}
+
+fn shader_module(device: &wgpu::Device, name: &str, wgsl: &str) -> wgpu::ShaderModule {
+    device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(&format!("{} Fragment Shader", name)),
+        source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+    })
+}
+
+/// A pipeline drawing a full-screen triangle with `fs_module`'s
+/// `entry_point` into a `format` target.
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    vs_module: &wgpu::ShaderModule,
+    fs_module: &wgpu::ShaderModule,
+    entry_point: &str,
+    format: wgpu::TextureFormat,
+    name: &str,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(&format!("{} Render Pipeline", name)),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: vs_module,
+            entry_point: "main",
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: fs_module,
+            entry_point,
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
+
+/// Draws a full-screen triangle with `pipeline` into `view`, with
+/// `bind_groups` in order from `@group(0)`, or just clears it if there's no
+/// pipeline.
+fn draw(encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, pipeline: Option<&wgpu::RenderPipeline>, bind_groups: &[&wgpu::BindGroup]) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Render Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+
+    if let Some(pipeline) = pipeline {
+        render_pass.set_pipeline(pipeline);
+        for (index, bind_group) in bind_groups.iter().enumerate() {
+            render_pass.set_bind_group(index as u32, bind_group, &[]);
+        }
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// A transition in progress, from shader `from` to the active one.
+struct Running {
+    from: String,
+    transition: Transition,
+    /// `uniforms.time` when it started.
+    start: f32,
+}
+
+/// What composite.wgsl reads as `transition`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct CompositeUniforms {
+    progress: f32,
+    mode: u32,
+    resolution: [f32; 2],
+}
+
+/// A texture a shader draws into, during a transition or as one of its passes.
+struct Frame {
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl Frame {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat, size: (u32, u32), label: &str) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d { width: size.0.max(1), height: size.1.max(1), depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Frame { _texture: texture, view }
+    }
+}
+
+/// The frames the two shaders of a transition draw into, and the pass that
+/// mixes them onto the screen.
+struct Composite {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    format: wgpu::TextureFormat,
+    outgoing: Frame,
+    incoming: Frame,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Composite {
+    fn new(device: &wgpu::Device, vs_module: &wgpu::ShaderModule, format: wgpu::TextureFormat, size: (u32, u32)) -> Self {
+        let texture = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture(1),
+                texture(2),
+            ],
+            label: Some("composite_bind_group_layout"),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Composite Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let module = shader_module(device, "Composite", include_str!("renderer/composite.wgsl"));
+        let pipeline = create_pipeline(device, &layout, vs_module, &module, "main", format, "Composite");
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Composite Uniform Buffer"),
+            size: std::mem::size_of::<CompositeUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let outgoing = Frame::new(device, format, size, "Outgoing Frame");
+        let incoming = Frame::new(device, format, size, "Incoming Frame");
+        let bind_group = Self::bind_group(device, &bind_group_layout, &uniform_buffer, &outgoing, &incoming);
+        Composite { pipeline, bind_group_layout, uniform_buffer, format, outgoing, incoming, bind_group }
+    }
+
+    fn bind_group(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, uniforms: &wgpu::Buffer, outgoing: &Frame, incoming: &Frame) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniforms.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&outgoing.view) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&incoming.view) },
+            ],
+            label: Some("composite_bind_group"),
+        })
+    }
+
+    /// New frames at `size`, since composite.wgsl reads them pixel for pixel.
+    fn resize(&mut self, device: &wgpu::Device, size: (u32, u32)) {
+        self.outgoing = Frame::new(device, self.format, size, "Outgoing Frame");
+        self.incoming = Frame::new(device, self.format, size, "Incoming Frame");
+        self.bind_group = Self::bind_group(device, &self.bind_group_layout, &self.uniform_buffer, &self.outgoing, &self.incoming);
+    }
 }
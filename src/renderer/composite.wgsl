@@ -0,0 +1,59 @@
+// Mixes the outgoing and incoming shaders' frames during a transition; see
+// src/shader/transition.rs. Drawn with vs.wgsl, like every shader.
+
+struct Transition {
+    // 0 shows only the outgoing frame, 1 only the incoming one.
+    progress: f32,
+    // shader::transition::Kind::mode.
+    mode: u32,
+    resolution: vec2<f32>,
+}
+
+@group(0) @binding(0) var<uniform> transition: Transition;
+@group(0) @binding(1) var outgoing: texture_2d<f32>;
+@group(0) @binding(2) var incoming: texture_2d<f32>;
+
+const DISSOLVE: u32 = 2u;
+const WIPE: u32 = 3u;
+
+// How soft the edge between the two frames is, in units of progress.
+const EDGE: f32 = 0.1;
+
+fn hash(p: vec2<f32>) -> f32 {
+    let h = dot(p, vec2<f32>(127.1, 311.7));
+    return fract(sin(h) * 43758.5453123);
+}
+
+fn noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = hash(i);
+    let b = hash(i + vec2<f32>(1.0, 0.0));
+    let c = hash(i + vec2<f32>(0.0, 1.0));
+    let d = hash(i + vec2<f32>(1.0, 1.0));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+// How much of the incoming frame shows where `threshold`, in 0..1, is
+// crossed at that progress: none at progress 0, all of it at 1.
+fn reveal(threshold: f32) -> f32 {
+    let front = transition.progress * (1.0 + 2.0 * EDGE) - EDGE;
+    return smoothstep(threshold - EDGE, threshold + EDGE, front);
+}
+
+@fragment
+fn main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(frag_coord.xy);
+    let from_color = textureLoad(outgoing, pixel, 0);
+    let to_color = textureLoad(incoming, pixel, 0);
+
+    var amount = transition.progress;
+    if transition.mode == DISSOLVE {
+        amount = reveal(noise(frag_coord.xy / 32.0));
+    } else if transition.mode == WIPE {
+        let half_size = 0.5 * transition.resolution;
+        amount = reveal(length(frag_coord.xy - half_size) / length(half_size));
+    }
+    return mix(from_color, to_color, amount);
+}
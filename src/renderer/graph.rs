@@ -0,0 +1,256 @@
+//! Runs a shader's [passes](crate::shader::graph) each frame. A [`Graph`]
+//! owns the textures its passes draw into and read, two for a pass whose
+//! previous output is read, and reallocates them when the screen's size
+//! changes.
+
+use crate::shader::graph::{self as spec, Input, Pass};
+
+use super::{create_pipeline, draw, shader_module, Frame};
+
+/// What every pass but the last draws in; it renders and filters everywhere,
+/// WebGL2 included.
+const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// What every graph uses.
+pub struct Shared {
+    pub vs_module: wgpu::ShaderModule,
+    /// `@group(0)`, the uniforms.
+    pub uniform_layout: wgpu::BindGroupLayout,
+    /// The pipeline layout of a single-pass shader, which has no `@group(1)`.
+    single_layout: wgpu::PipelineLayout,
+    /// The screen's format, which the last pass draws in.
+    format: wgpu::TextureFormat,
+    sampler: wgpu::Sampler,
+    noise: Frame,
+}
+
+impl Shared {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let vs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(crate::shader::VERTEX_SHADER.into()),
+        });
+        let uniform_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("uniform_bind_group_layout"),
+        });
+        let single_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[&uniform_layout],
+            push_constant_ranges: &[],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Input Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Shared { vs_module, uniform_layout, single_layout, format, sampler, noise: noise(device, queue) }
+    }
+}
+
+/// The `noise` input: random bytes in every channel, the same on every load.
+fn noise(device: &wgpu::Device, queue: &wgpu::Queue) -> Frame {
+    let size = wgpu::Extent3d { width: spec::NOISE_SIZE, height: spec::NOISE_SIZE, depth_or_array_layers: 1 };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Noise Texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: TEXTURE_FORMAT,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    // xorshift32; any fixed seed will do.
+    let mut state = 0x9e37_79b9u32;
+    let texels = (0..spec::NOISE_SIZE * spec::NOISE_SIZE * 4)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state >> 24) as u8
+        })
+        .collect::<Vec<u8>>();
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &texels,
+        wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(4 * spec::NOISE_SIZE), rows_per_image: Some(spec::NOISE_SIZE) },
+        size,
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    Frame { _texture: texture, view }
+}
+
+/// A pass and what it needs to run.
+struct Stage {
+    pass: Pass,
+    pipeline: wgpu::RenderPipeline,
+    /// Its `@group(1)`; `None` for a single-pass shader.
+    layout: Option<wgpu::BindGroupLayout>,
+    /// What it draws into, two if its previous output is read. Empty for the
+    /// last pass, which draws to the screen.
+    targets: Vec<Frame>,
+    /// Its `@group(1)` on even and odd frames.
+    bind_groups: Vec<wgpu::BindGroup>,
+}
+
+/// A shader's pipelines, one per pass, and their textures.
+pub struct Graph {
+    stages: Vec<Stage>,
+    /// The screen size the textures were allocated for.
+    size: Option<(u32, u32)>,
+    /// Which of a pass's two textures is this frame's.
+    parity: usize,
+}
+
+impl Graph {
+    /// Creates the pipelines of shader `name`, whose WGSL is `wgsl`. The
+    /// textures wait for the first [`Graph::begin_frame`].
+    pub fn new(device: &wgpu::Device, shared: &Shared, name: &str, wgsl: &str, passes: &[Pass]) -> Self {
+        let module = shader_module(device, name, wgsl);
+        if passes.is_empty() {
+            let pipeline = create_pipeline(device, &shared.single_layout, &shared.vs_module, &module, "main", shared.format, name);
+            let stage = Stage { pass: spec::single(), pipeline, layout: None, targets: Vec::new(), bind_groups: Vec::new() };
+            return Graph { stages: vec![stage], size: None, parity: 0 };
+        }
+
+        let bindings = spec::bindings(passes);
+        let stages = passes
+            .iter()
+            .enumerate()
+            .map(|(i, pass)| {
+                let mut entries = vec![wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                }];
+                entries.extend(pass.inputs.iter().map(|input| wgpu::BindGroupLayoutEntry {
+                    binding: binding(&bindings, input),
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                }));
+                let label = format!("{} {}", name, pass.name);
+                let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    entries: &entries,
+                    label: Some(&format!("{} Bind Group Layout", label)),
+                });
+                let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some(&format!("{} Pipeline Layout", label)),
+                    bind_group_layouts: &[&shared.uniform_layout, &layout],
+                    push_constant_ranges: &[],
+                });
+                let format = if i + 1 == passes.len() { shared.format } else { TEXTURE_FORMAT };
+                let pipeline = create_pipeline(device, &pipeline_layout, &shared.vs_module, &module, &pass.name, format, &label);
+                Stage { pass: pass.clone(), pipeline, layout: Some(layout), targets: Vec::new(), bind_groups: Vec::new() }
+            })
+            .collect();
+        Graph { stages, size: None, parity: 0 }
+    }
+
+    /// Moves on to the next frame, first reallocating the textures if the
+    /// screen is now `size`. A pass's previous output starts out black.
+    pub fn begin_frame(&mut self, device: &wgpu::Device, shared: &Shared, size: (u32, u32)) {
+        self.parity ^= 1;
+        if self.size == Some(size) {
+            return;
+        }
+        self.size = Some(size);
+        let passes: Vec<Pass> = self.stages.iter().map(|stage| stage.pass.clone()).collect();
+        let last = self.stages.len() - 1;
+        for (i, stage) in self.stages.iter_mut().enumerate() {
+            let count = if i == last {
+                0
+            } else if spec::has_feedback(&passes, &stage.pass.name) {
+                2
+            } else {
+                1
+            };
+            let label = format!("{} Texture", stage.pass.name);
+            stage.targets = (0..count).map(|_| Frame::new(device, TEXTURE_FORMAT, spec::size(stage.pass.scale, size), &label)).collect();
+        }
+
+        let bindings = spec::bindings(&passes);
+        let bind_groups: Vec<Vec<wgpu::BindGroup>> = self
+            .stages
+            .iter()
+            .map(|stage| {
+                let Some(layout) = &stage.layout else {
+                    return Vec::new();
+                };
+                (0..2)
+                    .map(|parity| {
+                        let mut entries = vec![wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::Sampler(&shared.sampler) }];
+                        entries.extend(stage.pass.inputs.iter().map(|input| wgpu::BindGroupEntry {
+                            binding: binding(&bindings, input),
+                            resource: wgpu::BindingResource::TextureView(self.view(input, parity, shared)),
+                        }));
+                        device.create_bind_group(&wgpu::BindGroupDescriptor {
+                            layout,
+                            entries: &entries,
+                            label: Some(&format!("{} Bind Group", stage.pass.name)),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        for (stage, bind_groups) in self.stages.iter_mut().zip(bind_groups) {
+            stage.bind_groups = bind_groups;
+        }
+    }
+
+    /// What `input` reads on a frame of `parity`.
+    fn view<'a>(&'a self, input: &Input, parity: usize, shared: &'a Shared) -> &'a wgpu::TextureView {
+        let targets = |name: &str| &self.stages.iter().find(|stage| stage.pass.name == name).expect("graph::check found every input").targets;
+        match input {
+            Input::Pass(name) => {
+                let targets = targets(name);
+                &targets[parity % targets.len()].view
+            }
+            Input::Previous(name) => {
+                let targets = targets(name);
+                &targets[(parity + 1) % targets.len()].view
+            }
+            Input::Noise => &shared.noise.view,
+        }
+    }
+
+    /// Runs the passes in order, the last drawing into `view`.
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, uniforms: &wgpu::BindGroup) {
+        for stage in &self.stages {
+            let target = match stage.targets.len() {
+                0 => view,
+                n => &stage.targets[self.parity % n].view,
+            };
+            match stage.bind_groups.get(self.parity) {
+                Some(inputs) => draw(encoder, target, Some(&stage.pipeline), &[uniforms, inputs]),
+                None => draw(encoder, target, Some(&stage.pipeline), &[uniforms]),
+            }
+        }
+    }
+}
+
+/// `input`'s `@binding` in `@group(1)`, as `shader::graph::wgsl` generates it.
+fn binding(bindings: &[Input], input: &Input) -> u32 {
+    bindings.iter().position(|i| i == input).map_or(0, |i| i as u32 + 1)
+}
@@ -0,0 +1,202 @@
+//! The typed resume model.
+//!
+//! Nothing in here touches the browser, so it can be used (and tested) natively;
+//! see `fetch` for the wasm side that loads `resume.json`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub mod format;
+pub mod jsonresume;
+pub mod pdf;
+pub mod validate;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Contact {
+    pub name: String,
+    pub title: Option<String>,
+    pub email: String,
+    pub phone: Option<String>,
+    pub linkedin: Option<String>,
+    pub github: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Section {
+    pub title: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Display labels for skill categories, keyed by the field name in `skills`.
+    #[serde(default)]
+    pub categories: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Sections {
+    pub summary: Option<Section>,
+    pub experience: Option<Section>,
+    pub projects: Option<Section>,
+    pub skills: Option<Section>,
+    pub education: Option<Section>,
+    pub certifications: Option<Section>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Experience {
+    pub title: String,
+    pub company: String,
+    pub dates: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub technologies: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Education {
+    pub degree: String,
+    pub university: String,
+    pub dates: String,
+    pub description: Option<String>,
+    pub gpa: Option<String>,
+    #[serde(default)]
+    pub coursework: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Skills {
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default)]
+    pub software_development: Vec<String>,
+    #[serde(default)]
+    pub other: Vec<String>,
+    #[serde(default)]
+    pub strengths: Vec<String>,
+    /// Any category not known above, e.g. `web` or `concepts` in older resumes.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Project {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub technologies: Vec<String>,
+    pub github: Option<String>,
+    pub demo: Option<String>,
+    pub gallery_link: Option<String>,
+    pub gallery_text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Certification {
+    pub name: String,
+    pub date: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Resume {
+    pub contact: Contact,
+    #[serde(default = "Sections::all_enabled")]
+    pub sections: Sections,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub experience: Vec<Experience>,
+    #[serde(default)]
+    pub education: Vec<Education>,
+    #[serde(default)]
+    pub skills: Skills,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub certifications: Vec<Certification>,
+}
+
+impl Resume {
+    pub fn from_json_str(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn from_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(value)
+    }
+}
+
+impl FromStr for Resume {
+    type Err = serde_json::Error;
+
+    fn from_str(json: &str) -> Result<Self, Self::Err> {
+        Self::from_json_str(json)
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Section {
+    pub fn new(title: &str) -> Self {
+        Self { title: title.to_string(), enabled: true, categories: BTreeMap::new() }
+    }
+}
+
+impl Sections {
+    /// The keys of the `sections` block, in the order the resume is laid out.
+    pub const NAMES: [&'static str; 6] = ["summary", "experience", "projects", "skills", "education", "certifications"];
+
+    /// Used when `resume.json` has no `sections` block at all: show everything.
+    pub fn all_enabled() -> Self {
+        let mut skills = Section::new("Skills");
+        for key in Skills::KNOWN_CATEGORIES {
+            skills.categories.insert(key.to_string(), key.replace('_', " "));
+        }
+        Self {
+            summary: Some(Section::new("Summary")),
+            experience: Some(Section::new("Experience")),
+            projects: Some(Section::new("Projects")),
+            skills: Some(skills),
+            education: Some(Section::new("Education")),
+            certifications: Some(Section::new("Certifications")),
+        }
+    }
+}
+
+impl Skills {
+    pub const KNOWN_CATEGORIES: [&'static str; 5] = ["languages", "tools", "software_development", "other", "strengths"];
+
+    /// All non-empty categories as `(key, skills)`: the known ones in `KNOWN_CATEGORIES`
+    /// order, then any others sorted by key.
+    pub fn categories(&self) -> Vec<(&str, &[String])> {
+        let known = [&self.languages, &self.tools, &self.software_development, &self.other, &self.strengths];
+        Self::KNOWN_CATEGORIES
+            .iter()
+            .copied()
+            .zip(known.into_iter().map(Vec::as_slice))
+            .chain(self.extra.iter().map(|(k, v)| (k.as_str(), v.as_slice())))
+            .filter(|(_, skills)| !skills.is_empty())
+            .collect()
+    }
+}
+
+/// Returns the section config if the section should be shown, mirroring the HTML view.
+pub fn enabled(section: &Option<Section>) -> Option<&Section> {
+    section.as_ref().filter(|s| s.enabled)
+}
+
+/// Turns a scheme-less link from `resume.json` (`github.com/...`) into a full URL.
+pub(crate) fn url_for(link: &str) -> String {
+    if link.contains("://") || link.starts_with("mailto:") {
+        link.to_string()
+    } else {
+        format!("https://{}", link)
+    }
+}
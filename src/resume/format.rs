@@ -0,0 +1,894 @@
+//! Text output formats for the resume. Every backend implements [`Formatter`];
+//! [`format`] walks the enabled sections in site order and calls into it, so
+//! section visibility and ordering only live in one place.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::ansi;
+
+use super::{enabled, url_for, Certification, Contact, Education, Experience, Project, Resume};
+
+/// One item of the experience, projects, education or certifications sections.
+pub struct Entry<'a> {
+    pub title: &'a str,
+    /// Company, university or issuer.
+    pub subtitle: Option<String>,
+    pub dates: Option<&'a str>,
+    pub description: Option<&'a str>,
+    /// A labelled tag list such as `("Tech", technologies)`.
+    pub tags: Option<(&'static str, &'a [String])>,
+    /// `(label, url)` pairs; urls always carry a scheme.
+    pub links: Vec<(String, String)>,
+}
+
+impl<'a> Entry<'a> {
+    pub fn experience(e: &'a Experience) -> Self {
+        Entry {
+            title: &e.title,
+            subtitle: Some(e.company.clone()),
+            dates: Some(&e.dates),
+            description: Some(e.description.as_str()).filter(|d| !d.is_empty()),
+            tags: Some(("Tech", e.technologies.as_slice())).filter(|(_, t)| !t.is_empty()),
+            links: Vec::new(),
+        }
+    }
+
+    pub fn project(p: &'a Project) -> Self {
+        let links = [("GitHub", &p.github), ("Demo", &p.demo)]
+            .into_iter()
+            .filter_map(|(label, link)| link.as_ref().map(|l| (label.to_string(), url_for(l))))
+            .chain(p.gallery_link.as_ref().map(|l| (p.gallery_text.clone().unwrap_or_else(|| "Gallery".to_string()), l.clone())))
+            .collect();
+        Entry {
+            title: &p.name,
+            subtitle: None,
+            dates: None,
+            description: Some(p.description.as_str()).filter(|d| !d.is_empty()),
+            tags: Some(("Tech", p.technologies.as_slice())).filter(|(_, t)| !t.is_empty()),
+            links,
+        }
+    }
+
+    pub fn education(e: &'a Education) -> Self {
+        let subtitle = match &e.gpa {
+            Some(gpa) => format!("{} - GPA: {}", e.university, gpa),
+            None => e.university.clone(),
+        };
+        Entry {
+            title: &e.degree,
+            subtitle: Some(subtitle),
+            dates: Some(&e.dates),
+            description: e.description.as_deref(),
+            tags: Some(("Coursework", e.coursework.as_slice())).filter(|(_, t)| !t.is_empty()),
+            links: Vec::new(),
+        }
+    }
+
+    pub fn certification(c: &'a Certification) -> Self {
+        Entry {
+            title: &c.name,
+            subtitle: None,
+            dates: c.date.as_deref(),
+            description: c.description.as_deref(),
+            tags: None,
+            links: Vec::new(),
+        }
+    }
+}
+
+pub trait Formatter {
+    fn header(&self, out: &mut String, contact: &Contact);
+    fn section(&self, out: &mut String, title: &str);
+    fn paragraph(&self, out: &mut String, text: &str);
+    fn entry(&self, out: &mut String, entry: &Entry);
+    fn skills(&self, out: &mut String, label: &str, skills: &[String]);
+    fn end_section(&self, _out: &mut String) {}
+    fn footer(&self, _out: &mut String) {}
+}
+
+/// Lays `resume` out with `formatter`, honoring the `sections` config.
+pub fn format(resume: &Resume, formatter: &dyn Formatter) -> String {
+    let mut out = String::new();
+    formatter.header(&mut out, &resume.contact);
+    let sections = &resume.sections;
+
+    if let Some(section) = enabled(&sections.summary).filter(|_| !resume.summary.is_empty()) {
+        formatter.section(&mut out, &section.title);
+        formatter.paragraph(&mut out, &resume.summary);
+        formatter.end_section(&mut out);
+    }
+    if let Some(section) = enabled(&sections.experience).filter(|_| !resume.experience.is_empty()) {
+        formatter.section(&mut out, &section.title);
+        for e in &resume.experience {
+            formatter.entry(&mut out, &Entry::experience(e));
+        }
+        formatter.end_section(&mut out);
+    }
+    if let Some(section) = enabled(&sections.projects).filter(|_| !resume.projects.is_empty()) {
+        formatter.section(&mut out, &section.title);
+        for p in &resume.projects {
+            formatter.entry(&mut out, &Entry::project(p));
+        }
+        formatter.end_section(&mut out);
+    }
+    if let Some(section) = enabled(&sections.skills) {
+        // Like the HTML view, only categories with a label in `sections.skills.categories` are shown.
+        let categories: Vec<(&String, &[String])> = resume
+            .skills
+            .categories()
+            .into_iter()
+            .filter_map(|(key, skills)| section.categories.get(key).map(|label| (label, skills)))
+            .collect();
+        if !categories.is_empty() {
+            formatter.section(&mut out, &section.title);
+            for (label, skills) in categories {
+                formatter.skills(&mut out, label, skills);
+            }
+            formatter.end_section(&mut out);
+        }
+    }
+    if let Some(section) = enabled(&sections.education).filter(|_| !resume.education.is_empty()) {
+        formatter.section(&mut out, &section.title);
+        for e in &resume.education {
+            formatter.entry(&mut out, &Entry::education(e));
+        }
+        formatter.end_section(&mut out);
+    }
+    if let Some(section) = enabled(&sections.certifications).filter(|_| !resume.certifications.is_empty()) {
+        formatter.section(&mut out, &section.title);
+        for c in &resume.certifications {
+            formatter.entry(&mut out, &Entry::certification(c));
+        }
+        formatter.end_section(&mut out);
+    }
+
+    formatter.footer(&mut out);
+    out
+}
+
+/// The contact line items as `(text, url)`, in display order.
+pub(crate) fn contact_items(contact: &Contact) -> Vec<(&str, Option<String>)> {
+    [
+        Some((contact.email.as_str(), Some(format!("mailto:{}", contact.email)))),
+        contact.phone.as_deref().map(|p| (p, None)),
+        contact.linkedin.as_deref().map(|l| (l, Some(url_for(l)))),
+        contact.github.as_deref().map(|g| (g, Some(url_for(g)))),
+        contact.location.as_deref().map(|l| (l, None)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Greedy word wrap of `text` to `width` columns; `None` keeps it on one line.
+pub fn wrap(text: &str, width: Option<usize>) -> Vec<String> {
+    match width {
+        Some(_) => wrap_words(text.split_whitespace(), width),
+        None => vec![text.to_string()],
+    }
+}
+
+/// Joins `words` with spaces, breaking lines before `width` columns. Words are
+/// never split, and escape sequences in them take up no room.
+pub fn wrap_words<S: AsRef<str>>(words: impl IntoIterator<Item = S>, width: Option<usize>) -> Vec<String> {
+    let width = width.unwrap_or(usize::MAX);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in words {
+        let word = word.as_ref();
+        if !line.is_empty() && ansi::width(&line) + 1 + ansi::width(word) > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Plain text, as shown by `cat resume`.
+pub struct PlainText {
+    /// Column to wrap at, or `None` to leave paragraphs on one line.
+    pub width: Option<usize>,
+}
+
+impl PlainText {
+    fn wrapped(&self, out: &mut String, indent: &str, text: &str) {
+        let width = self.width.map(|w| w.saturating_sub(indent.len()).max(20));
+        for line in wrap(text, width) {
+            let _ = writeln!(out, "{}{}", indent, line);
+        }
+    }
+}
+
+impl Formatter for PlainText {
+    fn header(&self, out: &mut String, contact: &Contact) {
+        let _ = writeln!(out, "\n{}", contact.name);
+        if let Some(title) = &contact.title {
+            let _ = writeln!(out, "{}", title);
+        }
+        let line = contact_items(contact).into_iter().map(|(text, _)| text).collect::<Vec<&str>>().join(" | ");
+        self.wrapped(out, "", &line);
+    }
+
+    fn section(&self, out: &mut String, title: &str) {
+        let _ = writeln!(out, "\n== {} ==", title);
+    }
+
+    fn paragraph(&self, out: &mut String, text: &str) {
+        self.wrapped(out, "", text);
+    }
+
+    fn entry(&self, out: &mut String, entry: &Entry) {
+        let _ = writeln!(out, "- {}", entry.title);
+        match (&entry.subtitle, entry.dates) {
+            (Some(subtitle), Some(dates)) => self.wrapped(out, "  ", &format!("{} ({})", subtitle, dates)),
+            (Some(subtitle), None) => self.wrapped(out, "  ", subtitle),
+            (None, Some(dates)) => self.wrapped(out, "  ", &format!("({})", dates)),
+            (None, None) => {}
+        }
+        if let Some(description) = entry.description {
+            self.wrapped(out, "  ", description);
+        }
+        if let Some((label, tags)) = entry.tags {
+            self.wrapped(out, "  ", &format!("{}: {}", label, tags.join(", ")));
+        }
+        if !entry.links.is_empty() {
+            let links = entry.links.iter().map(|(label, url)| format!("{} ({})", label, url)).collect::<Vec<String>>().join(", ");
+            self.wrapped(out, "  ", &format!("Links: {}", links));
+        }
+    }
+
+    fn skills(&self, out: &mut String, label: &str, skills: &[String]) {
+        self.wrapped(out, "", &format!("{}: {}", label, skills.join(", ")));
+    }
+}
+
+/// Plain text for a color terminal: bold headings, colored tags and clickable links.
+pub struct Ansi {
+    /// Column to wrap at, or `None` to leave paragraphs on one line.
+    pub width: Option<usize>,
+}
+
+/// Tag colors; each tag keeps the same one wherever it appears.
+const TAG_COLORS: [&str; 5] = [ansi::CYAN, ansi::GREEN, ansi::YELLOW, ansi::MAGENTA, ansi::BLUE];
+
+fn tag(name: &str) -> String {
+    let hash = name.bytes().fold(0usize, |hash, b| hash.wrapping_mul(31).wrapping_add(b as usize));
+    ansi::paint(&[TAG_COLORS[hash % TAG_COLORS.len()]], name)
+}
+
+impl Ansi {
+    /// Wraps `words`, which may already carry escape sequences, below `indent`.
+    fn wrapped<S: AsRef<str>>(&self, out: &mut String, indent: &str, words: impl IntoIterator<Item = S>) {
+        let width = self.width.map(|w| w.saturating_sub(indent.len()).max(20));
+        for line in wrap_words(words, width) {
+            let _ = writeln!(out, "{}{}", indent, line);
+        }
+    }
+
+    fn tags(&self, out: &mut String, indent: &str, label: &str, tags: &[String]) {
+        let label = ansi::paint(&[ansi::DIM], &format!("{}:", label));
+        self.wrapped(out, indent, std::iter::once(label).chain(tags.iter().map(|t| tag(t))));
+    }
+}
+
+impl Formatter for Ansi {
+    fn header(&self, out: &mut String, contact: &Contact) {
+        let _ = writeln!(out, "\n{}", ansi::paint(&[ansi::BOLD, ansi::CYAN], &contact.name));
+        if let Some(title) = &contact.title {
+            let _ = writeln!(out, "{}", ansi::paint(&[ansi::DIM], title));
+        }
+        let items = contact_items(contact).into_iter().map(|(text, url)| match url {
+            Some(url) => ansi::link(&url, text),
+            None => text.to_string(),
+        });
+        let separator = ansi::paint(&[ansi::DIM], "|");
+        let mut words = Vec::new();
+        for item in items {
+            if !words.is_empty() {
+                words.push(separator.clone());
+            }
+            words.push(item);
+        }
+        self.wrapped(out, "", words);
+    }
+
+    fn section(&self, out: &mut String, title: &str) {
+        let _ = writeln!(out, "\n{}", ansi::paint(&[ansi::BOLD, ansi::UNDERLINE], title));
+    }
+
+    fn paragraph(&self, out: &mut String, text: &str) {
+        self.wrapped(out, "", text.split_whitespace());
+    }
+
+    fn entry(&self, out: &mut String, entry: &Entry) {
+        let _ = writeln!(out, "- {}", ansi::paint(&[ansi::BOLD], entry.title));
+        let dates = entry.dates.map(|dates| ansi::paint(&[ansi::DIM], &format!("({})", dates)));
+        let subtitle = entry.subtitle.iter().flat_map(|s| s.split_whitespace()).map(str::to_string);
+        let line: Vec<String> = subtitle.chain(dates).collect();
+        if !line.is_empty() {
+            self.wrapped(out, "  ", line);
+        }
+        if let Some(description) = entry.description {
+            self.wrapped(out, "  ", description.split_whitespace());
+        }
+        if let Some((label, tags)) = entry.tags {
+            self.tags(out, "  ", label, tags);
+        }
+        if !entry.links.is_empty() {
+            let links = entry.links.iter().map(|(label, url)| ansi::link(url, &ansi::paint(&[ansi::UNDERLINE], label)));
+            self.wrapped(out, "  ", std::iter::once(ansi::paint(&[ansi::DIM], "Links:")).chain(links));
+        }
+    }
+
+    fn skills(&self, out: &mut String, label: &str, skills: &[String]) {
+        self.tags(out, "", label, skills);
+    }
+}
+
+/// GitHub-flavored Markdown.
+pub struct Markdown;
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '#' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Formatter for Markdown {
+    fn header(&self, out: &mut String, contact: &Contact) {
+        let _ = writeln!(out, "# {}\n", escape_markdown(&contact.name));
+        if let Some(title) = &contact.title {
+            let _ = writeln!(out, "**{}**\n", escape_markdown(title));
+        }
+        let items = contact_items(contact)
+            .into_iter()
+            .map(|(text, url)| match url {
+                Some(url) => format!("[{}]({})", escape_markdown(text), url),
+                None => escape_markdown(text),
+            })
+            .collect::<Vec<String>>()
+            .join(" · ");
+        let _ = writeln!(out, "{}", items);
+    }
+
+    fn section(&self, out: &mut String, title: &str) {
+        let _ = writeln!(out, "\n## {}\n", escape_markdown(title));
+    }
+
+    fn paragraph(&self, out: &mut String, text: &str) {
+        let _ = writeln!(out, "{}", escape_markdown(text));
+    }
+
+    fn entry(&self, out: &mut String, entry: &Entry) {
+        let _ = write!(out, "### {}", escape_markdown(entry.title));
+        if let Some(subtitle) = &entry.subtitle {
+            let _ = write!(out, " — {}", escape_markdown(subtitle));
+        }
+        out.push('\n');
+        if let Some(dates) = entry.dates {
+            let _ = writeln!(out, "*{}*", escape_markdown(dates));
+        }
+        if let Some(description) = entry.description {
+            let _ = writeln!(out, "\n{}", escape_markdown(description));
+        }
+        if let Some((label, tags)) = entry.tags {
+            let tags = tags.iter().map(|t| format!("`{}`", t.replace('`', "'"))).collect::<Vec<String>>().join(" ");
+            let _ = writeln!(out, "\n**{}:** {}", label, tags);
+        }
+        if !entry.links.is_empty() {
+            let links = entry.links.iter().map(|(label, url)| format!("[{}]({})", escape_markdown(label), url)).collect::<Vec<String>>().join(" · ");
+            let _ = writeln!(out, "\n{}", links);
+        }
+        out.push('\n');
+    }
+
+    fn skills(&self, out: &mut String, label: &str, skills: &[String]) {
+        let skills = skills.iter().map(|s| escape_markdown(s)).collect::<Vec<String>>().join(", ");
+        let _ = writeln!(out, "- **{}:** {}", escape_markdown(label), skills);
+    }
+}
+
+/// A standalone semantic HTML document.
+pub struct Html;
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn section_id(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+impl Formatter for Html {
+    fn header(&self, out: &mut String, contact: &Contact) {
+        let name = escape_html(&contact.name);
+        let _ = writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>", name);
+        let _ = writeln!(out, "<article class=\"resume\">\n<header>\n<h1>{}</h1>", name);
+        if let Some(title) = &contact.title {
+            let _ = writeln!(out, "<p class=\"title\">{}</p>", escape_html(title));
+        }
+        let _ = writeln!(out, "<ul class=\"contact\">");
+        for (text, url) in contact_items(contact) {
+            match url {
+                Some(url) => {
+                    let _ = writeln!(out, "<li><a href=\"{}\">{}</a></li>", escape_html(&url), escape_html(text));
+                }
+                None => {
+                    let _ = writeln!(out, "<li>{}</li>", escape_html(text));
+                }
+            }
+        }
+        let _ = writeln!(out, "</ul>\n</header>");
+    }
+
+    fn section(&self, out: &mut String, title: &str) {
+        let _ = writeln!(out, "<section id=\"{}\">\n<h2>{}</h2>", section_id(title), escape_html(title));
+    }
+
+    fn paragraph(&self, out: &mut String, text: &str) {
+        let _ = writeln!(out, "<p>{}</p>", escape_html(text));
+    }
+
+    fn entry(&self, out: &mut String, entry: &Entry) {
+        let _ = writeln!(out, "<article>\n<h3>{}</h3>", escape_html(entry.title));
+        let meta: Vec<String> = entry
+            .subtitle
+            .iter()
+            .map(|s| escape_html(s))
+            .chain(entry.dates.map(|d| format!("<time>{}</time>", escape_html(d))))
+            .collect();
+        if !meta.is_empty() {
+            let _ = writeln!(out, "<p class=\"meta\">{}</p>", meta.join(" • "));
+        }
+        if let Some(description) = entry.description {
+            let _ = writeln!(out, "<p>{}</p>", escape_html(description));
+        }
+        if let Some((label, tags)) = entry.tags {
+            let items = tags.iter().map(|t| format!("<li>{}</li>", escape_html(t))).collect::<String>();
+            let _ = writeln!(out, "<ul class=\"tags\" aria-label=\"{}\">{}</ul>", label, items);
+        }
+        if !entry.links.is_empty() {
+            let links = entry
+                .links
+                .iter()
+                .map(|(label, url)| format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(label)))
+                .collect::<Vec<String>>()
+                .join(" • ");
+            let _ = writeln!(out, "<p class=\"links\">{}</p>", links);
+        }
+        let _ = writeln!(out, "</article>");
+    }
+
+    fn skills(&self, out: &mut String, label: &str, skills: &[String]) {
+        let items = skills.iter().map(|s| format!("<li>{}</li>", escape_html(s))).collect::<String>();
+        let _ = writeln!(out, "<h3>{}</h3>\n<ul class=\"tags\">{}</ul>", escape_html(label), items);
+    }
+
+    fn end_section(&self, out: &mut String) {
+        let _ = writeln!(out, "</section>");
+    }
+
+    fn footer(&self, out: &mut String) {
+        let _ = writeln!(out, "</article>\n</body>\n</html>");
+    }
+}
+
+/// A `moderncv` document, ready to compile with pdflatex/xelatex or paste into Overleaf.
+pub struct Latex;
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The last path segment of a profile link, e.g. `github.com/falconnor4` → `falconnor4`.
+fn profile_handle(link: &str) -> &str {
+    link.trim_end_matches('/').rsplit('/').next().unwrap_or(link)
+}
+
+impl Formatter for Latex {
+    fn header(&self, out: &mut String, contact: &Contact) {
+        let (first, last) = contact.name.rsplit_once(' ').unwrap_or(("", contact.name.as_str()));
+        let _ = writeln!(out, "\\documentclass[11pt,a4paper,sans]{{moderncv}}");
+        let _ = writeln!(out, "\\moderncvstyle{{classic}}\n\\moderncvcolor{{blue}}");
+        let _ = writeln!(out, "\\usepackage[utf8]{{inputenc}}\n\\usepackage[scale=0.8]{{geometry}}\n");
+        let _ = writeln!(out, "\\name{{{}}}{{{}}}", escape_latex(first), escape_latex(last));
+        if let Some(title) = &contact.title {
+            let _ = writeln!(out, "\\title{{{}}}", escape_latex(title));
+        }
+        if let Some(location) = &contact.location {
+            let _ = writeln!(out, "\\address{{{}}}{{}}{{}}", escape_latex(location));
+        }
+        if let Some(phone) = &contact.phone {
+            let _ = writeln!(out, "\\phone[mobile]{{{}}}", escape_latex(phone));
+        }
+        let _ = writeln!(out, "\\email{{{}}}", escape_latex(&contact.email));
+        if let Some(github) = &contact.github {
+            let _ = writeln!(out, "\\social[github]{{{}}}", escape_latex(profile_handle(github)));
+        }
+        if let Some(linkedin) = &contact.linkedin {
+            let _ = writeln!(out, "\\social[linkedin]{{{}}}", escape_latex(profile_handle(linkedin)));
+        }
+        let _ = writeln!(out, "\n\\begin{{document}}\n\\makecvtitle");
+    }
+
+    fn section(&self, out: &mut String, title: &str) {
+        let _ = writeln!(out, "\n\\section{{{}}}", escape_latex(title));
+    }
+
+    fn paragraph(&self, out: &mut String, text: &str) {
+        let _ = writeln!(out, "\\cvitem{{}}{{{}}}", escape_latex(text));
+    }
+
+    fn entry(&self, out: &mut String, entry: &Entry) {
+        let mut details = Vec::new();
+        if let Some(description) = entry.description {
+            details.push(escape_latex(description));
+        }
+        if let Some((label, tags)) = entry.tags {
+            details.push(format!("\\textit{{{}:}} {}", label, escape_latex(&tags.join(", "))));
+        }
+        if !entry.links.is_empty() {
+            let links = entry
+                .links
+                .iter()
+                .map(|(label, url)| format!("\\href{{{}}}{{{}}}", url.replace('%', "\\%").replace('#', "\\#"), escape_latex(label)))
+                .collect::<Vec<String>>()
+                .join(" -- ");
+            details.push(links);
+        }
+        let _ = writeln!(
+            out,
+            "\\cventry{{{}}}{{{}}}{{{}}}{{}}{{}}{{{}}}",
+            escape_latex(entry.dates.unwrap_or("")),
+            escape_latex(entry.title),
+            escape_latex(entry.subtitle.as_deref().unwrap_or("")),
+            details.join("\\newline{}"),
+        );
+    }
+
+    fn skills(&self, out: &mut String, label: &str, skills: &[String]) {
+        let _ = writeln!(out, "\\cvitem{{{}}}{{{}}}", escape_latex(label), escape_latex(&skills.join(", ")));
+    }
+
+    fn footer(&self, out: &mut String) {
+        let _ = writeln!(out, "\n\\end{{document}}");
+    }
+}
+
+/// The output formats selectable with `cat resume --format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Markdown,
+    Html,
+    Latex,
+}
+
+impl Format {
+    /// Accepted names, which double as file extensions.
+    pub const NAMES: [&'static str; 4] = ["txt", "md", "html", "tex"];
+    pub const ALL: [Format; 4] = [Format::Text, Format::Markdown, Format::Html, Format::Latex];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Text => "txt",
+            Format::Markdown => "md",
+            Format::Html => "html",
+            Format::Latex => "tex",
+        }
+    }
+
+    /// Formats `resume`; `width` only affects plain text.
+    pub fn render(self, resume: &Resume, width: Option<usize>) -> String {
+        match self {
+            Format::Text => format(resume, &PlainText { width }),
+            Format::Markdown => format(resume, &Markdown),
+            Format::Html => format(resume, &Html),
+            Format::Latex => format(resume, &Latex),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "txt" | "text" | "plain" => Ok(Format::Text),
+            "md" | "markdown" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            "tex" | "latex" => Ok(Format::Latex),
+            other => Err(format!("unknown format '{}' (expected one of: {})", other, Format::NAMES.join(", "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Resume {
+        Resume::from_json_str(
+            r#"{
+                "contact": { "name": "Ada Lovelace", "title": "Analyst", "email": "ada@example.com", "github": "github.com/ada" },
+                "summary": "Writes programs for engines that don't exist yet.",
+                "experience": [
+                    { "title": "Collaborator", "company": "Babbage & Co", "dates": "1842 - 1843", "description": "Notes on the Analytical Engine.", "technologies": ["Punch cards"] }
+                ],
+                "projects": [
+                    { "name": "Note G", "description": "Bernoulli numbers.", "github": "github.com/ada/note-g", "gallery_link": "gallery.html#note-g" }
+                ],
+                "skills": { "languages": ["Mathematics", "French"] },
+                "education": [
+                    { "degree": "Private tutoring", "university": "London", "dates": "1830s", "description": "With Augustus De Morgan." }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn plain_text() {
+        let expected = "
+Ada Lovelace
+Analyst
+ada@example.com | github.com/ada
+
+== Summary ==
+Writes programs for engines that don't exist yet.
+
+== Experience ==
+- Collaborator
+  Babbage & Co (1842 - 1843)
+  Notes on the Analytical Engine.
+  Tech: Punch cards
+
+== Projects ==
+- Note G
+  Bernoulli numbers.
+  Links: GitHub (https://github.com/ada/note-g), Gallery (gallery.html#note-g)
+
+== Skills ==
+languages: Mathematics, French
+
+== Education ==
+- Private tutoring
+  London (1830s)
+  With Augustus De Morgan.
+";
+        assert_eq!(Format::Text.render(&sample(), None), expected);
+    }
+
+    #[test]
+    fn plain_text_wraps_below_the_indent() {
+        let text = Format::Text.render(&sample(), Some(24));
+        assert!(text.contains("\n  Notes on the\n  Analytical Engine.\n"), "{}", text);
+        assert!(text.lines().all(|line| line.len() <= 24 || !line.trim().contains(' ')), "{}", text);
+    }
+
+    #[test]
+    fn ansi_wraps_by_visible_width() {
+        let text = format(&sample(), &Ansi { width: Some(24) });
+        assert!(text.contains("\x1b[1;4mExperience\x1b[0m"), "{}", text);
+        assert!(text.contains("\x1b]8;;mailto:ada@example.com\x1b\\ada@example.com\x1b]8;;\x1b\\"), "{}", text);
+        assert!(text.lines().all(|line| ansi::width(line) <= 24 || !ansi::strip(line).trim().contains(' ')), "{}", text);
+    }
+
+    #[test]
+    fn wraps_wide_characters_by_columns() {
+        assert_eq!(wrap("日本語 日本語 日本語", Some(14)), ["日本語 日本語", "日本語"]);
+        assert_eq!(wrap_words(["\x1b[1mbold\x1b[0m", "word"], Some(9)), ["\x1b[1mbold\x1b[0m word"]);
+        assert_eq!(wrap("never wrapped", None), ["never wrapped"]);
+    }
+
+    #[test]
+    fn markdown() {
+        let expected = "# Ada Lovelace
+
+**Analyst**
+
+[ada@example.com](mailto:ada@example.com) · [github.com/ada](https://github.com/ada)
+
+## Summary
+
+Writes programs for engines that don't exist yet.
+
+## Experience
+
+### Collaborator — Babbage & Co
+*1842 - 1843*
+
+Notes on the Analytical Engine.
+
+**Tech:** `Punch cards`
+
+
+## Projects
+
+### Note G
+
+Bernoulli numbers.
+
+[GitHub](https://github.com/ada/note-g) · [Gallery](gallery.html#note-g)
+
+
+## Skills
+
+- **languages:** Mathematics, French
+
+## Education
+
+### Private tutoring — London
+*1830s*
+
+With Augustus De Morgan.
+
+";
+        assert_eq!(Format::Markdown.render(&sample(), None), expected);
+    }
+
+    #[test]
+    fn html() {
+        let expected = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Ada Lovelace</title>
+</head>
+<body>
+<article class="resume">
+<header>
+<h1>Ada Lovelace</h1>
+<p class="title">Analyst</p>
+<ul class="contact">
+<li><a href="mailto:ada@example.com">ada@example.com</a></li>
+<li><a href="https://github.com/ada">github.com/ada</a></li>
+</ul>
+</header>
+<section id="summary">
+<h2>Summary</h2>
+<p>Writes programs for engines that don't exist yet.</p>
+</section>
+<section id="experience">
+<h2>Experience</h2>
+<article>
+<h3>Collaborator</h3>
+<p class="meta">Babbage &amp; Co • <time>1842 - 1843</time></p>
+<p>Notes on the Analytical Engine.</p>
+<ul class="tags" aria-label="Tech"><li>Punch cards</li></ul>
+</article>
+</section>
+<section id="projects">
+<h2>Projects</h2>
+<article>
+<h3>Note G</h3>
+<p>Bernoulli numbers.</p>
+<p class="links"><a href="https://github.com/ada/note-g">GitHub</a> • <a href="gallery.html#note-g">Gallery</a></p>
+</article>
+</section>
+<section id="skills">
+<h2>Skills</h2>
+<h3>languages</h3>
+<ul class="tags"><li>Mathematics</li><li>French</li></ul>
+</section>
+<section id="education">
+<h2>Education</h2>
+<article>
+<h3>Private tutoring</h3>
+<p class="meta">London • <time>1830s</time></p>
+<p>With Augustus De Morgan.</p>
+</article>
+</section>
+</article>
+</body>
+</html>
+"#;
+        assert_eq!(Format::Html.render(&sample(), None), expected);
+    }
+
+    #[test]
+    fn html_escapes_text_and_attributes() {
+        let mut resume = sample();
+        resume.contact.name = "Ada <Byron> & \"Co\"".to_string();
+        resume.summary = "a < b && c > d".to_string();
+        resume.projects[0].gallery_link = Some("gallery.html?a=1&b=\"2\"".to_string());
+        let html = Format::Html.render(&resume, None);
+        assert!(html.contains("<title>Ada &lt;Byron&gt; &amp; &quot;Co&quot;</title>"), "{}", html);
+        assert!(html.contains("<h1>Ada &lt;Byron&gt; &amp; &quot;Co&quot;</h1>"), "{}", html);
+        assert!(html.contains("<p>a &lt; b &amp;&amp; c &gt; d</p>"), "{}", html);
+        assert!(html.contains("<a href=\"gallery.html?a=1&amp;b=&quot;2&quot;\">Gallery</a>"), "{}", html);
+    }
+
+    #[test]
+    fn html_section_ids() {
+        assert_eq!(section_id("Experience"), "experience");
+        assert_eq!(section_id("  Open Source & Talks "), "open-source-talks");
+    }
+
+    #[test]
+    fn latex() {
+        let expected = r#"\documentclass[11pt,a4paper,sans]{moderncv}
+\moderncvstyle{classic}
+\moderncvcolor{blue}
+\usepackage[utf8]{inputenc}
+\usepackage[scale=0.8]{geometry}
+
+\name{Ada}{Lovelace}
+\title{Analyst}
+\email{ada@example.com}
+\social[github]{ada}
+
+\begin{document}
+\makecvtitle
+
+\section{Summary}
+\cvitem{}{Writes programs for engines that don't exist yet.}
+
+\section{Experience}
+\cventry{1842 - 1843}{Collaborator}{Babbage \& Co}{}{}{Notes on the Analytical Engine.\newline{}\textit{Tech:} Punch cards}
+
+\section{Projects}
+\cventry{}{Note G}{}{}{}{Bernoulli numbers.\newline{}\href{https://github.com/ada/note-g}{GitHub} -- \href{gallery.html\#note-g}{Gallery}}
+
+\section{Skills}
+\cvitem{languages}{Mathematics, French}
+
+\section{Education}
+\cventry{1830s}{Private tutoring}{London}{}{}{With Augustus De Morgan.}
+
+\end{document}
+"#;
+        assert_eq!(Format::Latex.render(&sample(), None), expected);
+    }
+
+    #[test]
+    fn latex_escapes_special_characters() {
+        assert_eq!(escape_latex(r"& % $ # _ { } ~ ^ \"), r"\& \% \$ \# \_ \{ \} \textasciitilde{} \textasciicircum{} \textbackslash{}");
+        assert_eq!(escape_latex("C++ and C# at 100% {fast}"), r"C++ and C\# at 100\% \{fast\}");
+        let mut resume = sample();
+        resume.summary = "50% of $x_1$ ~ #1 {^}".to_string();
+        resume.contact.name = "Ada_L King".to_string();
+        let latex = Format::Latex.render(&resume, None);
+        assert!(latex.contains(r"\cvitem{}{50\% of \$x\_1\$ \textasciitilde{} \#1 \{\textasciicircum{}\}}"), "{}", latex);
+        assert!(latex.contains(r"\name{Ada\_L}{King}"), "{}", latex);
+        // Links keep their characters, except ones that would start a comment or a macro parameter.
+        assert!(latex.contains(r"\href{gallery.html\#note-g}{Gallery}"), "{}", latex);
+    }
+
+    #[test]
+    fn markdown_escapes_emphasis() {
+        let mut resume = sample();
+        resume.summary = "Uses *stars* and [brackets]".to_string();
+        assert!(Format::Markdown.render(&resume, None).contains("Uses \\*stars\\* and \\[brackets\\]"));
+    }
+}
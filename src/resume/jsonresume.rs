@@ -0,0 +1,584 @@
+//! Conversion between the site's resume model and the community JSON Resume
+//! schema (<https://jsonresume.org/schema>).
+//!
+//! Both directions are best-effort: anything that has no counterpart on the
+//! other side is dropped and reported as a warning instead of failing.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::{Certification, Contact, Education, Experience, Project, Resume, Sections, Skills};
+
+/// The converted value plus one `path: message` line for everything that didn't map.
+#[derive(Debug, Clone)]
+pub struct Conversion<T> {
+    pub value: T,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct JsonResume {
+    #[serde(default)]
+    basics: Basics,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    work: Vec<Work>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    education: Vec<JsonEducation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    skills: Vec<Skill>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    projects: Vec<JsonProject>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    certificates: Vec<Certificate>,
+    /// `volunteer`, `awards`, `publications`, ... which the site has no place for.
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Basics {
+    #[serde(default)]
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    profiles: Vec<Profile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    postal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country_code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Profile {
+    #[serde(default)]
+    network: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Work {
+    /// Older JSON Resume versions call this `company`.
+    #[serde(alias = "company", default)]
+    name: String,
+    #[serde(default)]
+    position: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    highlights: Vec<String>,
+    /// Not in the official schema (which allows extra properties); used for `technologies`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonEducation {
+    #[serde(default)]
+    institution: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    area: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    study_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    courses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Skill {
+    #[serde(default)]
+    name: String,
+    /// Not in the official schema; the site's key for the category, since
+    /// `name` is its display label.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonProject {
+    #[serde(default)]
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    highlights: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_date: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Certificate {
+    #[serde(default)]
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issuer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December",
+];
+
+/// `"February 2025"` → `"2025-02"`, `"2025"` → `"2025"`.
+fn to_iso_date(date: &str) -> Option<String> {
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    match parts.as_slice() {
+        [year] if year.len() == 4 && year.parse::<u32>().is_ok() => Some(year.to_string()),
+        [month, year] if year.len() == 4 && year.parse::<u32>().is_ok() => {
+            let month = month.trim_end_matches('.').to_lowercase();
+            let index = MONTHS.iter().position(|m| month.len() >= 3 && m.to_lowercase().starts_with(&month))?;
+            Some(format!("{}-{:02}", year, index + 1))
+        }
+        _ => None,
+    }
+}
+
+/// `"2025-02-14"` or `"2025-02"` → `"February 2025"`, `"2025"` → `"2025"`.
+fn from_iso_date(date: &str) -> Option<String> {
+    let mut parts = date.split('-');
+    let year = parts.next().filter(|y| y.len() == 4 && y.parse::<u32>().is_ok())?;
+    match parts.next() {
+        None => Some(year.to_string()),
+        Some(month) => {
+            let index = month.parse::<usize>().ok().filter(|m| (1..=12).contains(m))?;
+            Some(format!("{} {}", MONTHS[index - 1], year))
+        }
+    }
+}
+
+/// Splits `"February 2025 - Present"` into ISO start/end dates; an open end becomes `None`.
+fn split_dates(dates: &str, path: &str, warnings: &mut Vec<String>) -> (Option<String>, Option<String>) {
+    let (start, end) = match dates.split_once(" - ").or_else(|| dates.split_once('–')) {
+        Some((start, end)) => (start.trim(), Some(end.trim())),
+        None => (dates.trim(), None),
+    };
+    let mut convert = |part: &str| {
+        if part.eq_ignore_ascii_case("present") || part.is_empty() {
+            return None;
+        }
+        let iso = to_iso_date(part);
+        if iso.is_none() {
+            warnings.push(format!("{}: '{}' is not a recognizable date, dropped", path, part));
+        }
+        iso
+    };
+    let start = convert(start);
+    let end = end.and_then(&mut convert);
+    (start, end)
+}
+
+fn join_dates(start: Option<&str>, end: Option<&str>, path: &str, warnings: &mut Vec<String>) -> String {
+    let mut convert = |date: &str| {
+        from_iso_date(date).unwrap_or_else(|| {
+            warnings.push(format!("{}: '{}' is not an ISO 8601 date, kept as-is", path, date));
+            date.to_string()
+        })
+    };
+    match (start, end) {
+        (Some(start), Some(end)) => format!("{} - {}", convert(start), convert(end)),
+        (Some(start), None) => format!("{} - Present", convert(start)),
+        (None, Some(end)) => convert(end),
+        (None, None) => "Present".to_string(),
+    }
+}
+
+fn strip_scheme(url: &str) -> String {
+    url.trim_start_matches("https://").trim_start_matches("http://").trim_end_matches('/').to_string()
+}
+
+fn with_scheme(url: &str) -> String {
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+/// Converts a JSON Resume document into the site's model.
+pub fn import(value: Value) -> Result<Conversion<Resume>, serde_json::Error> {
+    let json: JsonResume = serde_json::from_value(value)?;
+    let mut warnings = Vec::new();
+    let basics = json.basics;
+
+    let mut contact = Contact {
+        name: basics.name,
+        title: basics.label,
+        email: basics.email.unwrap_or_else(|| {
+            warnings.push("basics.email: missing, left empty".to_string());
+            String::new()
+        }),
+        phone: basics.phone,
+        linkedin: None,
+        github: None,
+        location: basics.location.map(|l| {
+            [l.address, l.city, l.region, l.postal_code, l.country_code]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<String>>()
+                .join(", ")
+        }),
+    };
+    for (i, profile) in basics.profiles.into_iter().enumerate() {
+        let url = profile.url.as_deref().map(strip_scheme);
+        let slot = match profile.network.to_lowercase().as_str() {
+            "github" => &mut contact.github,
+            "linkedin" => &mut contact.linkedin,
+            _ => {
+                warnings.push(format!("basics.profiles[{}]: no field for '{}' profiles, dropped", i, profile.network));
+                continue;
+            }
+        };
+        *slot = url.or_else(|| profile.username.map(|u| format!("{}.com/{}", profile.network.to_lowercase(), u)));
+    }
+    if basics.url.is_some() {
+        warnings.push("basics.url: no personal website field, dropped".to_string());
+    }
+    if basics.image.is_some() {
+        warnings.push("basics.image: no picture field, dropped".to_string());
+    }
+
+    let experience = json.work.into_iter().enumerate().map(|(i, w)| {
+        let path = format!("work[{}]", i);
+        if !w.highlights.is_empty() {
+            warnings.push(format!("{}.highlights: no highlights field, dropped", path));
+        }
+        if w.url.is_some() {
+            warnings.push(format!("{}.url: no company url field, dropped", path));
+        }
+        Experience {
+            title: w.position,
+            company: w.name,
+            dates: join_dates(w.start_date.as_deref(), w.end_date.as_deref(), &path, &mut warnings),
+            description: w.summary.unwrap_or_default(),
+            technologies: w.keywords,
+        }
+    }).collect();
+
+    let education = json.education.into_iter().enumerate().map(|(i, e)| {
+        let path = format!("education[{}]", i);
+        if e.url.is_some() {
+            warnings.push(format!("{}.url: no institution url field, dropped", path));
+        }
+        Education {
+            degree: [e.study_type, e.area].into_iter().flatten().collect::<Vec<String>>().join(" "),
+            university: e.institution,
+            dates: join_dates(e.start_date.as_deref(), e.end_date.as_deref(), &path, &mut warnings),
+            description: None,
+            gpa: e.score,
+            coursework: e.courses,
+        }
+    }).collect();
+
+    let mut sections = Sections::all_enabled();
+    let mut skills = Skills::default();
+    if let Some(section) = sections.skills.as_mut() {
+        section.categories.clear();
+    }
+    for (i, skill) in json.skills.into_iter().enumerate() {
+        if skill.level.is_some() {
+            warnings.push(format!("skills[{}].level: no skill level field, dropped", i));
+        }
+        let key = skill.category.unwrap_or_else(|| snake_case(&skill.name));
+        let list = match key.as_str() {
+            "languages" => &mut skills.languages,
+            "tools" => &mut skills.tools,
+            "software_development" => &mut skills.software_development,
+            "other" => &mut skills.other,
+            "strengths" => &mut skills.strengths,
+            _ => skills.extra.entry(key.clone()).or_default(),
+        };
+        list.extend(skill.keywords);
+        if let Some(section) = sections.skills.as_mut() {
+            section.categories.insert(key, skill.name);
+        }
+    }
+
+    let projects = json.projects.into_iter().enumerate().map(|(i, p)| {
+        let path = format!("projects[{}]", i);
+        if !p.highlights.is_empty() {
+            warnings.push(format!("{}.highlights: no highlights field, dropped", path));
+        }
+        if p.start_date.is_some() || p.end_date.is_some() {
+            warnings.push(format!("{}: projects have no dates, dropped", path));
+        }
+        let url = p.url.as_deref().map(strip_scheme);
+        let (github, demo) = match url {
+            Some(url) if url.starts_with("github.com") => (Some(url), None),
+            other => (None, other),
+        };
+        Project {
+            name: p.name,
+            description: p.description.unwrap_or_default(),
+            technologies: p.keywords,
+            github,
+            demo,
+            gallery_link: None,
+            gallery_text: None,
+        }
+    }).collect();
+
+    let certifications = json.certificates.into_iter().enumerate().map(|(i, c)| {
+        if c.url.is_some() {
+            warnings.push(format!("certificates[{}].url: no certificate url field, dropped", i));
+        }
+        Certification {
+            name: c.name,
+            date: c.date.map(|d| from_iso_date(&d).unwrap_or(d)),
+            description: c.issuer.map(|issuer| format!("Issued by {}", issuer)),
+        }
+    }).collect();
+
+    for key in json.other.keys() {
+        if key != "$schema" && key != "meta" {
+            warnings.push(format!("{}: no equivalent section, dropped", key));
+        }
+    }
+
+    Ok(Conversion {
+        value: Resume {
+            contact,
+            sections,
+            summary: basics.summary.unwrap_or_default(),
+            experience,
+            education,
+            skills,
+            projects,
+            certifications,
+        },
+        warnings,
+    })
+}
+
+/// Converts the site's model into a JSON Resume document.
+pub fn export(resume: &Resume) -> Conversion<Value> {
+    let mut warnings = Vec::new();
+    let contact = &resume.contact;
+
+    let mut profiles = Vec::new();
+    for (network, url) in [("GitHub", &contact.github), ("LinkedIn", &contact.linkedin)] {
+        if let Some(url) = url {
+            profiles.push(Profile {
+                network: network.to_string(),
+                username: strip_scheme(url).rsplit('/').next().map(str::to_string),
+                url: Some(with_scheme(url)),
+            });
+        }
+    }
+    let basics = Basics {
+        name: contact.name.clone(),
+        label: contact.title.clone(),
+        image: None,
+        email: Some(contact.email.clone()),
+        phone: contact.phone.clone(),
+        url: None,
+        summary: Some(resume.summary.clone()).filter(|s| !s.is_empty()),
+        location: contact.location.as_ref().map(|address| Location { address: Some(address.clone()), ..Default::default() }),
+        profiles,
+    };
+
+    let work = resume.experience.iter().enumerate().map(|(i, e)| {
+        let (start_date, end_date) = split_dates(&e.dates, &format!("experience[{}].dates", i), &mut warnings);
+        Work {
+            name: e.company.clone(),
+            position: e.title.clone(),
+            url: None,
+            start_date,
+            end_date,
+            summary: Some(e.description.clone()).filter(|s| !s.is_empty()),
+            highlights: Vec::new(),
+            keywords: e.technologies.clone(),
+        }
+    }).collect();
+
+    let education = resume.education.iter().enumerate().map(|(i, e)| {
+        let path = format!("education[{}]", i);
+        if e.description.is_some() {
+            warnings.push(format!("{}.description: no education summary in JSON Resume, dropped", path));
+        }
+        let (start_date, end_date) = split_dates(&e.dates, &format!("{}.dates", path), &mut warnings);
+        JsonEducation {
+            institution: e.university.clone(),
+            url: None,
+            area: Some(e.degree.clone()),
+            study_type: None,
+            start_date,
+            end_date,
+            score: e.gpa.clone(),
+            courses: e.coursework.clone(),
+        }
+    }).collect();
+
+    let labels = resume.sections.skills.as_ref().map(|s| &s.categories);
+    let skills = resume.skills.categories().into_iter().map(|(key, list)| Skill {
+        name: labels.and_then(|l| l.get(key)).cloned().unwrap_or_else(|| key.replace('_', " ")),
+        category: Some(key.to_string()),
+        level: None,
+        keywords: list.to_vec(),
+    }).collect();
+
+    let projects = resume.projects.iter().enumerate().map(|(i, p)| {
+        let path = format!("projects[{}]", i);
+        if p.github.is_some() && p.demo.is_some() {
+            warnings.push(format!("{}.demo: JSON Resume projects have a single url, kept github", path));
+        }
+        if p.gallery_link.is_some() {
+            warnings.push(format!("{}.gallery_link: no gallery field, dropped", path));
+        }
+        JsonProject {
+            name: p.name.clone(),
+            description: Some(p.description.clone()).filter(|s| !s.is_empty()),
+            highlights: Vec::new(),
+            keywords: p.technologies.clone(),
+            url: p.github.as_ref().or(p.demo.as_ref()).map(|u| with_scheme(u)),
+            start_date: None,
+            end_date: None,
+        }
+    }).collect();
+
+    let certificates = resume.certifications.iter().enumerate().map(|(i, c)| {
+        let path = format!("certifications[{}]", i);
+        if c.description.is_some() {
+            warnings.push(format!("{}.description: no certificate description in JSON Resume, dropped", path));
+        }
+        // Certificates carry a single date; for a range keep where it started.
+        let date = c.date.as_deref().and_then(|d| split_dates(d, &format!("{}.date", path), &mut warnings).0);
+        Certificate { name: c.name.clone(), date, issuer: None, url: None }
+    }).collect();
+
+    for (key, section) in [
+        ("summary", &resume.sections.summary),
+        ("experience", &resume.sections.experience),
+        ("projects", &resume.sections.projects),
+        ("skills", &resume.sections.skills),
+        ("education", &resume.sections.education),
+        ("certifications", &resume.sections.certifications),
+    ] {
+        if super::enabled(section).is_none() {
+            warnings.push(format!("sections.{}: hidden on the site but exported anyway", key));
+        }
+    }
+
+    let json = JsonResume {
+        basics,
+        work,
+        education,
+        skills,
+        projects,
+        certificates,
+        other: BTreeMap::from([(
+            "$schema".to_string(),
+            Value::from("https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json"),
+        )]),
+    };
+    Conversion {
+        value: serde_json::to_value(json).expect("JSON Resume structs always serialize"),
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site_resume() -> Resume {
+        Resume::from_json_str(include_str!("../../www/resume.json")).unwrap()
+    }
+
+    #[test]
+    fn skills_round_trip() {
+        let resume = site_resume();
+        let imported = import(export(&resume).value).unwrap().value;
+        assert_eq!(serde_json::to_value(&imported.skills).unwrap(), serde_json::to_value(&resume.skills).unwrap());
+        assert!(imported.skills.extra.is_empty());
+        let labels = |r: &Resume| r.sections.skills.as_ref().unwrap().categories.clone();
+        assert_eq!(labels(&imported), labels(&resume));
+    }
+
+    #[test]
+    fn round_trip_keeps_the_rest() {
+        let resume = site_resume();
+        let imported = import(export(&resume).value).unwrap().value;
+        assert_eq!(imported.contact.name, resume.contact.name);
+        assert_eq!(imported.contact.github, resume.contact.github);
+        assert_eq!(imported.experience.len(), resume.experience.len());
+        for (a, b) in imported.experience.iter().zip(&resume.experience) {
+            assert_eq!((&a.title, &a.company, &a.technologies), (&b.title, &b.company, &b.technologies));
+        }
+    }
+
+    #[test]
+    fn imports_skills_without_a_category_by_name() {
+        let json = serde_json::json!({ "skills": [{ "name": "Languages", "keywords": ["Rust"] }, { "name": "Web Frameworks", "keywords": ["Axum"] }] });
+        let conversion = import(json).unwrap();
+        let skills = &conversion.value.skills;
+        assert_eq!(skills.languages, ["Rust"]);
+        assert_eq!(skills.extra.get("web_frameworks").map(Vec::as_slice), Some(&["Axum".to_string()][..]));
+        assert_eq!(conversion.value.sections.skills.unwrap().categories.get("web_frameworks").map(String::as_str), Some("Web Frameworks"));
+    }
+}
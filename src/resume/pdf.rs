@@ -0,0 +1,609 @@
+//! A single-column A4 PDF layout of the resume, written without any browser or
+//! Python help. Fonts are embedded (subset to the glyphs actually used) so the
+//! output looks the same in every viewer.
+
+use pdf_writer::types::{ActionType, AnnotationType, CidFontType, FontFlags, SystemInfo, UnicodeCmap};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::collections::BTreeMap;
+
+use super::format::contact_items;
+use super::{enabled, url_for, Resume};
+
+const REGULAR_TTF: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+const BOLD_TTF: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans-Bold.ttf");
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 40.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - MARGIN * 2.0;
+/// Horizontal shear used to fake an oblique style from the upright face.
+const OBLIQUE_SKEW: f32 = 0.2;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Regular,
+    Bold,
+    Italic,
+}
+
+struct Font {
+    name: &'static str,
+    data: &'static [u8],
+    face: ttf_parser::Face<'static>,
+    /// Glyphs used so far and the text they came from, for subsetting and `ToUnicode`.
+    used: BTreeMap<u16, char>,
+}
+
+impl Font {
+    fn new(name: &'static str, data: &'static [u8]) -> Self {
+        let face = ttf_parser::Face::parse(data, 0).expect("embedded font is a valid TrueType file");
+        Self { name, data, face, used: BTreeMap::new() }
+    }
+
+    fn to_pdf_units(&self, value: f32) -> f32 {
+        value * 1000.0 / self.face.units_per_em() as f32
+    }
+
+    fn glyph(&self, c: char) -> u16 {
+        self.face.glyph_index(c).map(|g| g.0).unwrap_or(0)
+    }
+
+    fn advance(&self, glyph: u16) -> f32 {
+        self.to_pdf_units(self.face.glyph_hor_advance(ttf_parser::GlyphId(glyph)).unwrap_or(0) as f32)
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        text.chars().map(|c| self.advance(self.glyph(c))).sum::<f32>() * size / 1000.0
+    }
+
+    /// Encodes `text` as big-endian glyph ids for an `Identity-H` font.
+    fn encode(&mut self, text: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            let glyph = self.glyph(c);
+            self.used.entry(glyph).or_insert(c);
+            bytes.extend_from_slice(&glyph.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+struct Page {
+    content: Content,
+    links: Vec<(Rect, String)>,
+}
+
+struct Layout {
+    fonts: [Font; 2],
+    pages: Vec<Page>,
+    /// Baseline of the next line, measured from the bottom of the page.
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        let mut layout = Self {
+            fonts: [Font::new("DejaVuSans", REGULAR_TTF), Font::new("DejaVuSans-Bold", BOLD_TTF)],
+            pages: Vec::new(),
+            y: 0.0,
+        };
+        layout.new_page();
+        layout
+    }
+
+    fn font_index(style: Style) -> usize {
+        match style {
+            Style::Bold => 1,
+            Style::Regular | Style::Italic => 0,
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Page { content: Content::new(), links: Vec::new() });
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Starts a new page unless `height` more points fit on the current one.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn width(&self, text: &str, style: Style, size: f32) -> f32 {
+        self.fonts[Self::font_index(style)].width(text, size)
+    }
+
+    fn draw(&mut self, text: &str, style: Style, size: f32, x: f32) {
+        let index = Self::font_index(style);
+        let encoded = self.fonts[index].encode(text);
+        let skew = if style == Style::Italic { OBLIQUE_SKEW } else { 0.0 };
+        let font_name = if index == 0 { Name(b"F0") } else { Name(b"F1") };
+        let content = &mut self.pages.last_mut().unwrap().content;
+        content.begin_text();
+        content.set_font(font_name, size);
+        content.set_text_matrix([1.0, 0.0, skew, 1.0, x, self.y]);
+        content.show(Str(&encoded));
+        content.end_text();
+    }
+
+    fn link(&mut self, x: f32, width: f32, size: f32, url: &str) {
+        let rect = Rect::new(x, self.y - size * 0.25, x + width, self.y + size * 0.9);
+        self.pages.last_mut().unwrap().links.push((rect, url.to_string()));
+    }
+
+    /// Greedy word wrap to `width` points. A word wider than that on its own,
+    /// such as a long URL, is broken wherever it has to be.
+    fn wrap(&self, text: &str, style: Style, size: f32, width: f32) -> Vec<String> {
+        let fits = |line: &str| self.width(line, style, size) <= width;
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if fits(&candidate) {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if !fits(&line) && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn paragraph(&mut self, text: &str, style: Style, size: f32, leading: f32) {
+        for line in self.wrap(text, style, size, CONTENT_WIDTH) {
+            self.ensure_space(leading);
+            self.draw(&line, style, size, MARGIN);
+            self.y -= leading;
+        }
+    }
+
+    /// A bold heading on the left with an optional right-aligned note (dates) on the same line.
+    /// The note wraps within half the line and the heading within what's left beside it.
+    fn heading(&mut self, left: &str, right: Option<&str>) {
+        let right = right.map(|r| self.wrap(r, Style::Regular, 10.5, CONTENT_WIDTH / 2.0)).unwrap_or_default();
+        let right_width = right.iter().map(|line| self.width(line, Style::Regular, 10.5)).fold(0.0, f32::max);
+        let gap = if right.is_empty() { 0.0 } else { 12.0 };
+        let left = self.wrap(left, Style::Bold, 10.5, CONTENT_WIDTH - right_width - gap);
+        let rows = left.len().max(right.len());
+        // Keep the heading on the same page as the first lines under it.
+        self.ensure_space(13.0 * (rows + 2) as f32);
+        for row in 0..rows {
+            if let Some(line) = left.get(row) {
+                self.draw(line, Style::Bold, 10.5, MARGIN);
+            }
+            if let Some(line) = right.get(row) {
+                let width = self.width(line, Style::Regular, 10.5);
+                self.draw(line, Style::Regular, 10.5, PAGE_WIDTH - MARGIN - width);
+            }
+            self.y -= 13.0;
+        }
+    }
+
+    /// `text` centered, on as many lines of `leading` as it takes.
+    fn centered(&mut self, text: &str, style: Style, size: f32, leading: f32) {
+        for line in self.wrap(text, style, size, CONTENT_WIDTH) {
+            let width = self.width(&line, style, size);
+            self.draw(&line, style, size, (PAGE_WIDTH - width) / 2.0);
+            self.y -= leading;
+        }
+    }
+
+    /// `items` centered with `separator` between them, as many to a line as
+    /// fit, each with its own link rect.
+    fn centered_items(&mut self, items: &[(&str, Option<String>)], separator: &str, size: f32, leading: f32) {
+        let style = Style::Regular;
+        let separator_width = self.width(separator, style, size);
+        let mut lines: Vec<Vec<(String, f32, Option<&str>)>> = Vec::new();
+        let mut line = Vec::new();
+        let mut line_width = 0.0;
+        for (text, url) in items {
+            for piece in self.wrap(text, style, size, CONTENT_WIDTH) {
+                let width = self.width(&piece, style, size);
+                if !line.is_empty() && line_width + separator_width + width > CONTENT_WIDTH {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+                if !line.is_empty() {
+                    line_width += separator_width;
+                }
+                line_width += width;
+                line.push((piece, width, url.as_deref()));
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+
+        for line in lines {
+            let total = line.iter().map(|(_, width, _)| width).sum::<f32>() + separator_width * (line.len() - 1) as f32;
+            let mut x = (PAGE_WIDTH - total) / 2.0;
+            for (i, (text, width, url)) in line.iter().enumerate() {
+                if i > 0 {
+                    self.draw(separator, style, size, x);
+                    x += separator_width;
+                }
+                self.draw(text, style, size, x);
+                if let Some(url) = url {
+                    self.link(x, *width, size, url);
+                }
+                x += width;
+            }
+            self.y -= leading;
+        }
+    }
+
+    fn rule(&mut self) {
+        let y = self.y;
+        let content = &mut self.pages.last_mut().unwrap().content;
+        content.set_line_width(0.7);
+        content.move_to(MARGIN, y);
+        content.line_to(PAGE_WIDTH - MARGIN, y);
+        content.stroke();
+    }
+
+    fn section(&mut self, title: &str) {
+        self.ensure_space(15.0 + 13.0 * 2.0);
+        self.draw(&title.to_uppercase(), Style::Bold, 12.0, MARGIN);
+        self.y -= 15.0;
+    }
+
+    /// `"Label: value"` with the label in bold; a long value wraps in line
+    /// with where it starts.
+    fn labeled(&mut self, label: &str, value: &str, url: Option<&str>) {
+        let label = format!("{}: ", label);
+        let offset = self.width(&label, Style::Bold, 10.0);
+        for (i, line) in self.wrap(value, Style::Regular, 10.0, CONTENT_WIDTH - offset).into_iter().enumerate() {
+            self.ensure_space(12.0);
+            if i == 0 {
+                self.draw(&label, Style::Bold, 10.0, MARGIN);
+            }
+            self.draw(&line, Style::Regular, 10.0, MARGIN + offset);
+            if let Some(url) = url {
+                let width = self.width(&line, Style::Regular, 10.0);
+                self.link(MARGIN + offset, width, 10.0, url);
+            }
+            self.y -= 12.0;
+        }
+    }
+}
+
+fn lay_out(resume: &Resume) -> Layout {
+    let mut layout = Layout::new();
+    let contact = &resume.contact;
+
+    // --- Header ---
+    layout.y -= 4.0;
+    layout.centered(&contact.name, Style::Bold, 24.0, 24.0);
+    if let Some(title) = &contact.title {
+        layout.centered(title, Style::Regular, 11.0, 16.0);
+    }
+    layout.centered_items(&contact_items(contact), "  •  ", 10.0, 12.0);
+    layout.rule();
+    layout.y -= 22.0;
+
+    let sections = &resume.sections;
+    if let Some(section) = enabled(&sections.summary).filter(|_| !resume.summary.is_empty()) {
+        layout.section(&section.title);
+        layout.paragraph(&resume.summary, Style::Regular, 10.0, 12.0);
+        layout.y -= 15.0;
+    }
+
+    if let Some(section) = enabled(&sections.experience).filter(|_| !resume.experience.is_empty()) {
+        layout.section(&section.title);
+        for exp in &resume.experience {
+            layout.heading(&exp.title, Some(&exp.dates));
+            layout.paragraph(&exp.company, Style::Italic, 10.0, 13.0);
+            layout.paragraph(&exp.description, Style::Regular, 10.0, 12.0);
+            layout.y -= 8.0;
+        }
+        layout.y -= 7.0;
+    }
+
+    if let Some(section) = enabled(&sections.projects).filter(|_| !resume.projects.is_empty()) {
+        layout.section(&section.title);
+        for proj in &resume.projects {
+            layout.heading(&proj.name, None);
+            layout.paragraph(&proj.description, Style::Regular, 10.0, 12.0);
+            if let Some(github) = &proj.github {
+                layout.labeled("GitHub", github, Some(&url_for(github)));
+            }
+            if let Some(demo) = &proj.demo {
+                layout.labeled("Demo", demo, Some(&url_for(demo)));
+            }
+            if let Some(gallery) = &proj.gallery_link {
+                // A relative link only means something on the site, so it's shown but not linked.
+                layout.labeled("Gallery", gallery, Some(gallery.as_str()).filter(|g| g.contains("://")));
+            }
+            layout.y -= 8.0;
+        }
+        layout.y -= 7.0;
+    }
+
+    if let Some(section) = enabled(&sections.skills) {
+        let categories: Vec<(&String, String)> = resume
+            .skills
+            .categories()
+            .into_iter()
+            .filter_map(|(key, skills)| section.categories.get(key).map(|label| (label, skills.join("  •  "))))
+            .collect();
+        if !categories.is_empty() {
+            layout.section(&section.title);
+            for (label, skills) in categories {
+                layout.heading(label, None);
+                layout.paragraph(&skills, Style::Regular, 10.0, 12.0);
+                layout.y -= 8.0;
+            }
+            layout.y -= 7.0;
+        }
+    }
+
+    if let Some(section) = enabled(&sections.education).filter(|_| !resume.education.is_empty()) {
+        layout.section(&section.title);
+        for edu in &resume.education {
+            layout.heading(&edu.degree, Some(&edu.dates));
+            layout.paragraph(&edu.university, Style::Italic, 10.0, 13.0);
+            if let Some(gpa) = &edu.gpa {
+                layout.labeled("GPA", gpa, None);
+            }
+            if let Some(description) = &edu.description {
+                layout.paragraph(description, Style::Regular, 10.0, 12.0);
+            }
+            if !edu.coursework.is_empty() {
+                layout.paragraph(&format!("Coursework: {}", edu.coursework.join(", ")), Style::Regular, 10.0, 12.0);
+            }
+            layout.y -= 8.0;
+        }
+        layout.y -= 7.0;
+    }
+
+    if let Some(section) = enabled(&sections.certifications).filter(|_| !resume.certifications.is_empty()) {
+        layout.section(&section.title);
+        for cert in &resume.certifications {
+            layout.heading(&cert.name, cert.date.as_deref());
+            if let Some(description) = &cert.description {
+                layout.paragraph(description, Style::Regular, 10.0, 12.0);
+            }
+            layout.y -= 8.0;
+        }
+    }
+
+    layout
+}
+
+fn compress(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+}
+
+/// Writes `font` as a subset `Type0`/`CIDFontType2` font and returns its reference.
+fn write_font(pdf: &mut Pdf, next_ref: &mut impl FnMut() -> Ref, font: &Font, tag: &str) -> Ref {
+    let type0_ref = next_ref();
+    let cid_ref = next_ref();
+    let descriptor_ref = next_ref();
+    let cmap_ref = next_ref();
+    let file_ref = next_ref();
+
+    let base_font = format!("{}+{}", tag, font.name);
+    let system_info = SystemInfo { registry: Str(b"Adobe"), ordering: Str(b"Identity"), supplement: 0 };
+
+    pdf.type0_font(type0_ref)
+        .base_font(Name(base_font.as_bytes()))
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid_ref)
+        .to_unicode(cmap_ref);
+
+    let mut cid = pdf.cid_font(cid_ref);
+    cid.subtype(CidFontType::Type2)
+        .base_font(Name(base_font.as_bytes()))
+        .system_info(system_info)
+        .font_descriptor(descriptor_ref)
+        .default_width(0.0)
+        .cid_to_gid_map_predefined(Name(b"Identity"));
+    let mut widths = cid.widths();
+    for &glyph in font.used.keys() {
+        widths.consecutive(glyph, [font.advance(glyph)]);
+    }
+    widths.finish();
+    cid.finish();
+
+    let face = &font.face;
+    let bbox = face.global_bounding_box();
+    pdf.font_descriptor(descriptor_ref)
+        .name(Name(base_font.as_bytes()))
+        .flags(FontFlags::NON_SYMBOLIC)
+        .bbox(Rect::new(
+            font.to_pdf_units(bbox.x_min as f32),
+            font.to_pdf_units(bbox.y_min as f32),
+            font.to_pdf_units(bbox.x_max as f32),
+            font.to_pdf_units(bbox.y_max as f32),
+        ))
+        .italic_angle(0.0)
+        .ascent(font.to_pdf_units(face.ascender() as f32))
+        .descent(font.to_pdf_units(face.descender() as f32))
+        .cap_height(font.to_pdf_units(face.capital_height().unwrap_or(face.ascender()) as f32))
+        .stem_v(80.0)
+        .font_file2(file_ref);
+
+    let mut cmap = UnicodeCmap::new(Name(b"Custom"), system_info);
+    for (&glyph, &c) in &font.used {
+        cmap.pair(glyph, c);
+    }
+    pdf.cmap(cmap_ref, &cmap.finish());
+
+    let glyphs: Vec<u16> = font.used.keys().copied().collect();
+    let subset = subsetter::subset(font.data, 0, subsetter::Profile::pdf(&glyphs)).unwrap_or_else(|_| font.data.to_vec());
+    pdf.stream(file_ref, &compress(&subset)).filter(Filter::FlateDecode);
+
+    type0_ref
+}
+
+/// Lays out `resume` and returns the bytes of the finished PDF.
+pub fn render(resume: &Resume) -> Vec<u8> {
+    let layout = lay_out(resume);
+
+    let mut pdf = Pdf::new();
+    let mut next_id = 1;
+    let mut next_ref = || {
+        next_id += 1;
+        Ref::new(next_id - 1)
+    };
+    let catalog_ref = next_ref();
+    let tree_ref = next_ref();
+    let info_ref = next_ref();
+    let page_refs: Vec<(Ref, Ref)> = layout.pages.iter().map(|_| (next_ref(), next_ref())).collect();
+    let regular_ref = write_font(&mut pdf, &mut next_ref, &layout.fonts[0], "RSMREG");
+    let bold_ref = write_font(&mut pdf, &mut next_ref, &layout.fonts[1], "RSMBLD");
+
+    pdf.catalog(catalog_ref).pages(tree_ref);
+    pdf.pages(tree_ref).kids(page_refs.iter().map(|(page, _)| *page)).count(page_refs.len() as i32);
+    pdf.document_info(info_ref)
+        .title(TextStr(&resume.contact.name))
+        .producer(TextStr("rendered-resume"));
+
+    for (page, (page_ref, content_ref)) in layout.pages.into_iter().zip(page_refs) {
+        let mut writer = pdf.page(page_ref);
+        writer.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+        writer.parent(tree_ref);
+        writer.contents(content_ref);
+        let mut resources = writer.resources();
+        let mut fonts = resources.fonts();
+        fonts.pair(Name(b"F0"), regular_ref);
+        fonts.pair(Name(b"F1"), bold_ref);
+        fonts.finish();
+        resources.finish();
+        let mut annotations = writer.annotations();
+        for (rect, url) in &page.links {
+            let mut annotation = annotations.push();
+            annotation.subtype(AnnotationType::Link).rect(*rect).border(0.0, 0.0, 0.0, None);
+            annotation.action().action_type(ActionType::Uri).uri(Str(url.as_bytes()));
+        }
+        annotations.finish();
+        writer.finish();
+        pdf.stream(content_ref, &compress(&page.content.finish())).filter(Filter::FlateDecode);
+    }
+
+    pdf.finish()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    /// A string drawn by one `Tj`, decoded through its font's `ToUnicode` CMap.
+    struct Run {
+        bold: bool,
+        size: f32,
+        x: f32,
+        y: f32,
+        text: String,
+    }
+
+    fn runs(document: &lopdf::Document, page: lopdf::ObjectId) -> Vec<Run> {
+        let cmaps: BTreeMap<Vec<u8>, BTreeMap<u16, char>> = document
+            .get_page_fonts(page)
+            .unwrap()
+            .into_iter()
+            .map(|(name, font)| {
+                let cmap = document.get_object(font.get(b"ToUnicode").unwrap().as_reference().unwrap()).unwrap();
+                let cmap = String::from_utf8(cmap.as_stream().unwrap().get_plain_content().unwrap()).unwrap();
+                let pairs = cmap
+                    .lines()
+                    .filter_map(|line| {
+                        let (glyph, c) = line.strip_prefix('<')?.strip_suffix('>')?.split_once("> <")?;
+                        Some((u16::from_str_radix(glyph, 16).ok()?, char::from_u32(u32::from_str_radix(c, 16).ok()?)?))
+                    })
+                    .collect();
+                (name, pairs)
+            })
+            .collect();
+        let content = lopdf::content::Content::decode(&document.get_page_content(page).unwrap()).unwrap();
+        let (mut font, mut size, mut position) = (Vec::new(), 0.0, (0.0, 0.0));
+        let mut runs = Vec::new();
+        for operation in content.operations {
+            let number = |i: usize| operation.operands[i].as_float().unwrap();
+            match operation.operator.as_str() {
+                "Tf" => (font, size) = (operation.operands[0].as_name().unwrap().to_vec(), number(1)),
+                "Tm" => position = (number(4), number(5)),
+                "Tj" => {
+                    let glyphs = operation.operands[0].as_str().unwrap();
+                    let text = glyphs.chunks(2).map(|g| cmaps[&font][&u16::from_be_bytes([g[0], g[1]])]).collect();
+                    runs.push(Run { bold: font == b"F1", size, x: position.0, y: position.1, text });
+                }
+                _ => {}
+            }
+        }
+        runs
+    }
+
+    /// Every run on every page, after checking the document parses.
+    fn render_runs(resume: &Resume) -> Vec<Vec<Run>> {
+        let document = lopdf::Document::load_mem(&render(resume)).expect("the xref and objects parse");
+        document.get_pages().into_values().map(|page| runs(&document, page)).collect()
+    }
+
+    fn site_resume() -> Resume {
+        Resume::from_json_str(include_str!("../../www/resume.json")).unwrap()
+    }
+
+    #[test]
+    fn renders_the_site_resume() {
+        let resume = site_resume();
+        let pages = render_runs(&resume);
+        assert_eq!(pages.len(), lay_out(&resume).pages.len());
+        let text: Vec<&str> = pages.iter().flatten().map(|run| run.text.as_str()).collect();
+        assert!(text.contains(&resume.contact.name.as_str()), "{:?}", text);
+        assert!(text.contains(&resume.contact.email.as_str()), "{:?}", text);
+    }
+
+    #[test]
+    fn long_values_stay_inside_the_margins() {
+        let long = "a-very-long-value-without-any-spaces-at-all-that-would-otherwise-run-straight-off-the-page";
+        let mut resume = site_resume();
+        resume.contact.location = Some(format!("Somewhere {} far away", long));
+        resume.experience[0].title = format!("{} {}", resume.experience[0].title, "with a title long enough to reach its dates ".repeat(3));
+        resume.experience[0].dates = "January 2020 - Present, with a long note about the dates".to_string();
+        resume.projects[0].github = Some(format!("github.com/{}", long));
+
+        let fonts = Layout::new().fonts;
+        for page in render_runs(&resume) {
+            let extent = |run: &Run| (run.x, run.x + fonts[run.bold as usize].width(&run.text, run.size));
+            for run in &page {
+                let (left, right) = extent(run);
+                assert!(left >= MARGIN - 0.01 && right <= PAGE_WIDTH - MARGIN + 0.01, "'{}' spans {}..{}", run.text, left, right);
+            }
+            for a in &page {
+                for b in page.iter().filter(|b| b.y == a.y && b.x > a.x) {
+                    assert!(extent(a).1 <= extent(b).0, "'{}' runs into '{}'", a.text, b.text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn includes_education_descriptions_and_gallery_links() {
+        let resume = site_resume();
+        let mut text = String::new();
+        for run in render_runs(&resume).iter().flatten() {
+            text.push_str(&run.text);
+            text.push(' ');
+        }
+        let gallery = resume.projects.iter().find_map(|p| p.gallery_link.as_deref()).expect("the site resume links a gallery");
+        assert!(text.contains(&format!("Gallery:  {}", gallery)), "{}", text);
+        for education in &resume.education {
+            let description = education.description.as_deref().expect("the site resume describes its education");
+            let first_words = description.split_whitespace().take(3).collect::<Vec<&str>>().join(" ");
+            assert!(text.contains(&first_words), "{} not in {}", first_words, text);
+        }
+    }
+}
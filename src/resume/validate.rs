@@ -0,0 +1,285 @@
+use serde_json::{Map, Value};
+use std::fmt;
+
+use super::Sections;
+
+/// One problem found in `resume.json`, addressed by its JSON path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+const CONTACT_FIELDS: &[(&str, bool)] = &[
+    ("name", true),
+    ("title", false),
+    ("email", true),
+    ("phone", false),
+    ("linkedin", false),
+    ("github", false),
+    ("location", false),
+];
+const EXPERIENCE_FIELDS: &[(&str, bool)] = &[("title", true), ("company", true), ("dates", true), ("description", false)];
+const EDUCATION_FIELDS: &[(&str, bool)] = &[("degree", true), ("university", true), ("dates", true), ("description", false), ("gpa", false)];
+const PROJECT_FIELDS: &[(&str, bool)] = &[
+    ("name", true),
+    ("description", false),
+    ("github", false),
+    ("demo", false),
+    ("gallery_link", false),
+    ("gallery_text", false),
+];
+const CERTIFICATION_FIELDS: &[(&str, bool)] = &[("name", true), ("date", false), ("description", false)];
+
+/// Parses `json` and checks it against the resume schema.
+pub fn validate_str(json: &str) -> Vec<ValidationError> {
+    match serde_json::from_str::<Value>(json) {
+        Ok(value) => validate(&value),
+        Err(e) => vec![ValidationError {
+            path: format!("line {}, column {}", e.line(), e.column()),
+            message: e.to_string(),
+        }],
+    }
+}
+
+/// Checks `value` against the resume schema and returns every problem found.
+pub fn validate(value: &Value) -> Vec<ValidationError> {
+    let mut v = Validator::default();
+    let Some(root) = v.object(value, "$") else {
+        return v.errors;
+    };
+
+    match root.get("contact") {
+        Some(contact) => v.record(contact, "contact", CONTACT_FIELDS, &[]),
+        None => v.error("contact", "missing"),
+    }
+    v.optional_string(root, "", "summary");
+    v.list(root, "experience", EXPERIENCE_FIELDS, &["technologies"]);
+    v.list(root, "education", EDUCATION_FIELDS, &["coursework"]);
+    v.list(root, "projects", PROJECT_FIELDS, &["technologies"]);
+    v.list(root, "certifications", CERTIFICATION_FIELDS, &[]);
+
+    let skills = root.get("skills").and_then(|skills| v.object(skills, "skills"));
+    if let Some(skills) = skills {
+        for (key, list) in skills {
+            v.string_list(list, &format!("skills.{}", key));
+        }
+    }
+    if let Some(sections) = root.get("sections") {
+        v.sections(sections, skills);
+    }
+
+    for key in root.keys() {
+        if !["contact", "sections", "summary", "experience", "education", "skills", "projects", "certifications"].contains(&key.as_str()) {
+            v.error(key, "unknown field");
+        }
+    }
+    v.errors
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<ValidationError>,
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.errors.push(ValidationError { path: path.to_string(), message: message.into() });
+    }
+
+    fn mismatch(&mut self, path: &str, expected: &str, found: &Value) {
+        self.error(path, format!("expected {}, found {}", expected, type_name(found)));
+    }
+
+    fn object<'a>(&mut self, value: &'a Value, path: &str) -> Option<&'a Map<String, Value>> {
+        match value {
+            Value::Object(map) => Some(map),
+            other => {
+                self.mismatch(path, "object", other);
+                None
+            }
+        }
+    }
+
+    fn required_string(&mut self, obj: &Map<String, Value>, path: &str, key: &str) {
+        let path = join(path, key);
+        match obj.get(key) {
+            None => self.error(&path, "missing"),
+            Some(Value::String(s)) if s.trim().is_empty() => self.error(&path, "empty"),
+            Some(Value::String(_)) => {}
+            Some(other) => self.mismatch(&path, "string", other),
+        }
+    }
+
+    fn optional_string(&mut self, obj: &Map<String, Value>, path: &str, key: &str) {
+        match obj.get(key) {
+            None | Some(Value::String(_)) => {}
+            Some(other) => self.mismatch(&join(path, key), "string", other),
+        }
+    }
+
+    fn string_list(&mut self, value: &Value, path: &str) {
+        let Value::Array(items) = value else {
+            self.mismatch(path, "array of strings", value);
+            return;
+        };
+        for (i, item) in items.iter().enumerate() {
+            if !item.is_string() {
+                self.mismatch(&format!("{}[{}]", path, i), "string", item);
+            }
+        }
+    }
+
+    /// Checks an object with the given string `fields` and string-list `lists`, flagging unknown keys.
+    fn record(&mut self, value: &Value, path: &str, fields: &[(&str, bool)], lists: &[&str]) {
+        let Some(obj) = self.object(value, path) else {
+            return;
+        };
+        for &(key, required) in fields {
+            if required {
+                self.required_string(obj, path, key);
+            } else {
+                self.optional_string(obj, path, key);
+            }
+        }
+        for &key in lists {
+            if let Some(list) = obj.get(key) {
+                self.string_list(list, &join(path, key));
+            }
+        }
+        for key in obj.keys() {
+            if !fields.iter().any(|(f, _)| f == key) && !lists.contains(&key.as_str()) {
+                self.error(&join(path, key), "unknown field");
+            }
+        }
+    }
+
+    fn list(&mut self, root: &Map<String, Value>, key: &str, fields: &[(&str, bool)], lists: &[&str]) {
+        match root.get(key) {
+            None => {}
+            Some(Value::Array(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.record(item, &format!("{}[{}]", key, i), fields, lists);
+                }
+            }
+            Some(other) => self.mismatch(key, "array", other),
+        }
+    }
+
+    fn sections(&mut self, value: &Value, skills: Option<&Map<String, Value>>) {
+        let Some(sections) = self.object(value, "sections") else {
+            return;
+        };
+        for (key, section) in sections {
+            let path = format!("sections.{}", key);
+            if !Sections::NAMES.contains(&key.as_str()) {
+                self.error(&path, "unknown section");
+                continue;
+            }
+            let Some(section) = self.object(section, &path) else {
+                continue;
+            };
+            self.required_string(section, &path, "title");
+            if let Some(enabled) = section.get("enabled").filter(|e| !e.is_boolean()) {
+                self.mismatch(&join(&path, "enabled"), "boolean", enabled);
+            }
+            if let Some(categories) = section.get("categories") {
+                let categories_path = join(&path, "categories");
+                if key != "skills" {
+                    self.error(&categories_path, "only the skills section has categories");
+                    continue;
+                }
+                let Some(categories) = self.object(categories, &categories_path) else {
+                    continue;
+                };
+                for (category, label) in categories {
+                    let category_path = join(&categories_path, category);
+                    if !label.is_string() {
+                        self.mismatch(&category_path, "string", label);
+                    }
+                    if !skills.is_some_and(|skills| skills.contains_key(category)) {
+                        self.error(&category_path, "no matching skills key");
+                    }
+                }
+            }
+            for field in section.keys() {
+                if !["title", "enabled", "categories"].contains(&field.as_str()) {
+                    self.error(&join(&path, field), "unknown field");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(path: &str, message: &str) -> ValidationError {
+        ValidationError { path: path.to_string(), message: message.to_string() }
+    }
+
+    #[test]
+    fn the_site_resume_is_valid() {
+        assert_eq!(validate_str(include_str!("../../www/resume.json")), Vec::new());
+    }
+
+    #[test]
+    fn reports_missing_fields_by_path() {
+        let json = r#"{
+            "contact": { "email": "ada@example.com" },
+            "experience": [{ "title": "Collaborator", "dates": "1843" }]
+        }"#;
+        assert_eq!(validate_str(json), vec![error("contact.name", "missing"), error("experience[0].company", "missing")]);
+    }
+
+    #[test]
+    fn reports_wrong_types_and_unknown_fields() {
+        let json = r#"{
+            "contact": { "name": "Ada", "email": "ada@example.com", "fax": "none" },
+            "skills": { "languages": ["French", 3] },
+            "sections": { "skills": { "title": "Skills", "enabled": "yes", "categories": { "tools": "Tools" } } }
+        }"#;
+        assert_eq!(
+            validate_str(json),
+            vec![
+                error("contact.fax", "unknown field"),
+                error("skills.languages[1]", "expected string, found number"),
+                error("sections.skills.enabled", "expected boolean, found string"),
+                error("sections.skills.categories.tools", "no matching skills key"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_by_line_and_column() {
+        let errors = validate_str("{\n  \"contact\": {\n    \"name\": \"Ada\",\n  }\n}");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "line 4, column 3");
+    }
+}
@@ -0,0 +1,222 @@
+//! Terminal state that survives a reload: command history, environment
+//! variables and aliases.
+//!
+//! The page keeps a [`Session`] as JSON in localStorage. History expansion
+//! (`!!`, `!n`, `!-n`) and reverse search work on it here, so `index.js` only
+//! draws the prompt. A few variables configure the page: `SHADER`, `COLUMNS`
+//! and `NO_COLOR`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::shell::Terminal;
+
+pub mod commands;
+
+/// How many lines of history are kept; older ones are dropped first.
+pub const HISTORY_LIMIT: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Session {
+    history: Vec<String>,
+    /// How many lines were dropped from the front of `history`, so `!n`
+    /// keeps meaning the same line.
+    dropped: usize,
+    env: BTreeMap<String, String>,
+    aliases: BTreeMap<String, String>,
+}
+
+impl Session {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// The remembered lines, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// The number `history` shows for `history()[index]`, which `!n` refers to.
+    pub fn history_number(&self, index: usize) -> usize {
+        self.dropped + index + 1
+    }
+
+    pub fn clear_history(&mut self) {
+        self.dropped += self.history.len();
+        self.history.clear();
+    }
+
+    /// Adds `line` to the history, unless it's blank or repeats the last line.
+    pub fn push(&mut self, line: &str) {
+        if line.trim().is_empty() || self.history.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.history.push(line.to_string());
+        if self.history.len() > HISTORY_LIMIT {
+            let excess = self.history.len() - HISTORY_LIMIT;
+            self.history.drain(..excess);
+            self.dropped += excess;
+        }
+    }
+
+    /// Expands `line` and records it, returning the line to run.
+    pub fn record(&mut self, line: &str) -> Result<String, String> {
+        let line = self.expand(line)?;
+        self.push(&line);
+        Ok(line)
+    }
+
+    /// Replaces `!!` (the last line), `!n` (line n) and `!-n` (n lines back).
+    /// Nothing inside single quotes or after a backslash is expanded; as in
+    /// bash, double quotes don't stop it, but a `'` inside them is just a letter.
+    pub fn expand(&self, line: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(line.len());
+        let mut chars = line.char_indices();
+        // The quote we're inside, if any.
+        let mut quote = None;
+        while let Some((i, c)) = chars.next() {
+            let quoted = quote == Some('\'');
+            match c {
+                '\'' | '"' if quote.is_none() => quote = Some(c),
+                _ if quote == Some(c) => quote = None,
+                '\\' if !quoted => {
+                    out.push(c);
+                    if let Some((_, next)) = chars.next() {
+                        out.push(next);
+                    }
+                    continue;
+                }
+                '!' if !quoted => {
+                    let rest = &line[i + 1..];
+                    let len = designator_len(rest);
+                    if len > 0 {
+                        let designator = &rest[..len];
+                        let entry = self.event(designator).ok_or_else(|| format!("!{}: event not found", designator))?;
+                        out.push_str(entry);
+                        // Designators are ASCII, so bytes and chars agree.
+                        for _ in 0..len {
+                            chars.next();
+                        }
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            out.push(c);
+        }
+        Ok(out)
+    }
+
+    /// The line `!designator` refers to.
+    fn event(&self, designator: &str) -> Option<&str> {
+        let index = match designator {
+            "!" => self.history.len().checked_sub(1)?,
+            _ => match designator.strip_prefix('-') {
+                Some(back) => self.history.len().checked_sub(back.parse().ok()?)?,
+                None => designator.parse::<usize>().ok()?.checked_sub(self.dropped + 1)?,
+            },
+        };
+        self.history.get(index).map(String::as_str)
+    }
+
+    /// The index of the newest line before `before` that contains `query`, as
+    /// each Ctrl-R steps further back.
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.history[..before.min(self.history.len())].iter().rposition(|line| line.contains(query))
+    }
+
+    pub fn var(&self, name: &str) -> Option<&str> {
+        self.env.get(name).map(String::as_str)
+    }
+
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.env
+    }
+
+    pub fn set_var(&mut self, name: &str, value: &str) {
+        self.env.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn unset_var(&mut self, name: &str) -> bool {
+        self.env.remove(name).is_some()
+    }
+
+    pub fn alias(&self, name: &str) -> Option<&str> {
+        self.aliases.get(name).map(String::as_str)
+    }
+
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    pub fn set_alias(&mut self, name: &str, value: &str) {
+        self.aliases.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn unalias(&mut self, name: &str) -> bool {
+        self.aliases.remove(name).is_some()
+    }
+
+    /// A terminal `columns` wide, unless `COLUMNS` says otherwise; any
+    /// non-empty `NO_COLOR` turns colors off, as at no-color.org.
+    pub fn terminal(&self, columns: usize) -> Terminal {
+        let columns = self.var("COLUMNS").and_then(|c| c.parse().ok()).filter(|c| *c > 0).unwrap_or(columns);
+        Terminal { columns, color: self.var("NO_COLOR").is_none_or(str::is_empty) }
+    }
+}
+
+/// The length of the event designator (`!`, `n` or `-n`) that `rest` starts
+/// with, or 0 for a `!` that isn't history expansion, like in `a != b`.
+fn designator_len(rest: &str) -> usize {
+    if rest.starts_with('!') {
+        return 1;
+    }
+    let sign = usize::from(rest.starts_with('-'));
+    match rest[sign..].bytes().take_while(u8::is_ascii_digit).count() {
+        0 => 0,
+        digits => sign + digits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        let mut session = Session::default();
+        session.push("ls /projects");
+        session.push("cat resume");
+        session
+    }
+
+    #[test]
+    fn expands_history_outside_single_quotes() {
+        let session = session();
+        let cases = [
+            ("!!", "cat resume"),
+            ("!1 | wc", "ls /projects | wc"),
+            ("echo !-2", "echo ls /projects"),
+            ("echo '!!'", "echo '!!'"),
+            ("echo \\!!", "echo \\!!"),
+            ("echo \"!!\"", "echo \"cat resume\""),
+            ("echo \"it's\" !!", "echo \"it's\" cat resume"),
+            ("echo \"it's '!!'\"", "echo \"it's 'cat resume'\""),
+            ("echo '\"' !!", "echo '\"' cat resume"),
+            ("echo \"a\\\"'\" !!", "echo \"a\\\"'\" cat resume"),
+            ("echo !", "echo !"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(session.expand(line).as_deref(), Ok(expected), "{}", line);
+        }
+    }
+
+    #[test]
+    fn reports_missing_events() {
+        assert_eq!(session().expand("!9"), Err("!9: event not found".to_string()));
+    }
+}
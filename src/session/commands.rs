@@ -0,0 +1,403 @@
+//! Commands that read or change the shared [`Session`].
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::Session;
+use crate::command::{usage_error, Arg, ArgKind, Command, Context, Registry, RunFuture, Value};
+use crate::shell::filters::strs;
+use crate::shell::Output;
+
+/// Adds the session commands to `registry`, all working on `session`.
+pub fn register(registry: &mut Registry, session: &Rc<RefCell<Session>>) {
+    registry.register(History(session.clone()));
+    registry.register(Alias(session.clone()));
+    registry.register(Unalias(session.clone()));
+    registry.register(Export(session.clone()));
+    registry.register(Unset(session.clone()));
+    registry.register(Env(session.clone()));
+}
+
+/// `value` single-quoted so the shell reads it back unchanged.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Whether `name` can be used as `$name`.
+fn is_var_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `name` would be read back as a single plain word.
+fn is_alias_name(name: &str) -> bool {
+    !name.is_empty() && !name.chars().any(|c| c.is_whitespace() || "'\"\\|;&>$=".contains(c))
+}
+
+pub struct History(Rc<RefCell<Session>>);
+
+impl Command for History {
+    fn name(&self) -> &'static str {
+        "history"
+    }
+
+    fn usage(&self) -> &'static str {
+        "history [-c] [N]"
+    }
+
+    fn description(&self) -> &'static str {
+        "List earlier commands; rerun one with !N, or the last with !!"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "-c", kind: ArgKind::Flag, help: "forget every command" },
+            Arg { name: "N", kind: ArgKind::Positional(Value::Text), help: "only the last N commands" },
+        ]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let count = match strs(args).as_slice() {
+                [] => usize::MAX,
+                ["-c"] => {
+                    self.0.borrow_mut().clear_history();
+                    return Output::default();
+                }
+                [n] => match n.parse() {
+                    Ok(n) => n,
+                    Err(_) => return usage_error(self),
+                },
+                _ => return usage_error(self),
+            };
+            let session = self.0.borrow();
+            let history = session.history();
+            let skip = history.len().saturating_sub(count);
+            let lines: Vec<String> = history
+                .iter()
+                .enumerate()
+                .skip(skip)
+                .map(|(i, line)| format!("{:>5}  {}", session.history_number(i), line))
+                .collect();
+            lines.join("\n").into()
+        })
+    }
+}
+
+pub struct Alias(Rc<RefCell<Session>>);
+
+impl Command for Alias {
+    fn name(&self) -> &'static str {
+        "alias"
+    }
+
+    fn usage(&self) -> &'static str {
+        "alias [NAME[=VALUE]...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Define or list aliases, e.g. alias ll='ls -l'"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "NAME=VALUE", kind: ArgKind::Positional(Value::Text), help: "make NAME at the start of a command run VALUE" }]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let mut session = self.0.borrow_mut();
+            if args.is_empty() {
+                let lines: Vec<String> = session.aliases().iter().map(|(name, value)| format!("alias {}={}", name, quote(value))).collect();
+                return lines.join("\n").into();
+            }
+            let mut out = Output::default();
+            let mut errors = Vec::new();
+            for arg in args {
+                match arg.split_once('=') {
+                    Some((name, value)) if is_alias_name(name) => session.set_alias(name, value),
+                    Some((name, _)) => errors.push(format!("alias: '{}': invalid alias name", name)),
+                    None => match session.alias(arg) {
+                        Some(value) => out.stdout.push_str(&format!("alias {}={}\n", arg, quote(value))),
+                        None => errors.push(format!("alias: {}: not found", arg)),
+                    },
+                }
+            }
+            if !errors.is_empty() {
+                out.stderr = errors.join("\n");
+                out.status = 1;
+            }
+            out
+        })
+    }
+}
+
+pub struct Unalias(Rc<RefCell<Session>>);
+
+impl Command for Unalias {
+    fn name(&self) -> &'static str {
+        "unalias"
+    }
+
+    fn usage(&self) -> &'static str {
+        "unalias -a | NAME..."
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove aliases"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "-a", kind: ArgKind::Flag, help: "remove every alias" },
+            Arg { name: "NAME", kind: ArgKind::Positional(Value::Text), help: "alias to remove" },
+        ]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let mut session = self.0.borrow_mut();
+            match strs(args).as_slice() {
+                [] => usage_error(self),
+                ["-a"] => {
+                    let names: Vec<String> = session.aliases().keys().cloned().collect();
+                    for name in names {
+                        session.unalias(&name);
+                    }
+                    Output::default()
+                }
+                names => {
+                    let missing: Vec<String> = names
+                        .iter()
+                        .filter(|name| !session.unalias(name))
+                        .map(|name| format!("unalias: {}: not found", name))
+                        .collect();
+                    if missing.is_empty() {
+                        Output::default()
+                    } else {
+                        Output::error(missing.join("\n"))
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// The variables with a meaning to the page, for `help export`.
+const SPECIAL_VARS: &str = "SHADER (background shader on load), COLUMNS (wrap width), NO_COLOR (plain output)";
+
+pub struct Export(Rc<RefCell<Session>>);
+
+impl Command for Export {
+    fn name(&self) -> &'static str {
+        "export"
+    }
+
+    fn usage(&self) -> &'static str {
+        "export [NAME=VALUE...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Set environment variables, or list them"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "NAME=VALUE", kind: ArgKind::Positional(Value::Text), help: SPECIAL_VARS }]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let mut session = self.0.borrow_mut();
+            if args.is_empty() {
+                let lines: Vec<String> = session.vars().iter().map(|(name, value)| format!("export {}={}", name, quote(value))).collect();
+                return lines.join("\n").into();
+            }
+            let mut errors = Vec::new();
+            for arg in args {
+                // `export NAME` keeps a variable that's already set, as in sh.
+                let (name, value) = match arg.split_once('=') {
+                    Some((name, value)) => (name, value.to_string()),
+                    None => (arg.as_str(), session.var(arg).unwrap_or_default().to_string()),
+                };
+                if is_var_name(name) {
+                    session.set_var(name, &value);
+                } else {
+                    errors.push(format!("export: '{}': not a valid identifier", name));
+                }
+            }
+            if errors.is_empty() {
+                Output::default()
+            } else {
+                Output::error(errors.join("\n"))
+            }
+        })
+    }
+}
+
+pub struct Unset(Rc<RefCell<Session>>);
+
+impl Command for Unset {
+    fn name(&self) -> &'static str {
+        "unset"
+    }
+
+    fn usage(&self) -> &'static str {
+        "unset NAME..."
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove environment variables"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "NAME", kind: ArgKind::Positional(Value::Text), help: "variable to remove" }]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            if args.is_empty() {
+                return usage_error(self);
+            }
+            // Like sh, unsetting a variable that isn't set is fine.
+            let mut session = self.0.borrow_mut();
+            for name in args {
+                session.unset_var(name);
+            }
+            Output::default()
+        })
+    }
+}
+
+pub struct Env(Rc<RefCell<Session>>);
+
+impl Command for Env {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn usage(&self) -> &'static str {
+        "env"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the environment variables"
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            if !args.is_empty() {
+                return usage_error(self);
+            }
+            let session = self.0.borrow();
+            let lines: Vec<String> = session.vars().iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            lines.join("\n").into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::block_on;
+    use crate::shell::parse::parse;
+
+    /// Parses `line` as the shell would and runs its one command on `session`.
+    fn run(session: &Rc<RefCell<Session>>, line: &str) -> Output {
+        let script = parse(line, &|_| None).unwrap();
+        let argv: Vec<String> = script.items[0].1.commands[0].argv.iter().map(|word| word.expand(|_| None)).collect();
+        let mut registry = Registry::new();
+        register(&mut registry, session);
+        block_on(registry.run(&argv, None, None))
+    }
+
+    #[test]
+    fn quoted_values_read_back_unchanged() {
+        let values = ["ls -l", "", "it's", "''", "a\\b", "$HOME \"x\" | y; z"];
+        for value in values {
+            let session = Rc::new(RefCell::new(Session::default()));
+            session.borrow_mut().set_alias("x", value);
+            session.borrow_mut().set_var("X", value);
+            let alias = run(&session, "alias x").stdout;
+            let export = run(&session, "export").stdout;
+
+            let again = Rc::new(RefCell::new(Session::default()));
+            assert_eq!(run(&again, alias.trim_end()).status, 0, "{}", alias);
+            assert_eq!(run(&again, &export).status, 0, "{}", export);
+            assert_eq!(again.borrow().alias("x"), Some(value), "{}", alias);
+            assert_eq!(again.borrow().var("X"), Some(value), "{}", export);
+        }
+
+        let session = Rc::new(RefCell::new(Session::default()));
+        run(&session, r"alias x='it'\''s'");
+        assert_eq!(session.borrow().alias("x"), Some("it's"));
+        assert_eq!(run(&session, "alias").stdout, r"alias x='it'\''s'");
+    }
+
+    #[test]
+    fn names_must_read_back_as_one_word() {
+        let aliases = [("ll", true), ("..", true), ("git-st", true), ("", false), ("a b", false), ("a|b", false), ("a;b", false), ("a>b", false), ("$a", false), ("'a'", false)];
+        for (name, ok) in aliases {
+            assert_eq!(is_alias_name(name), ok, "{:?}", name);
+        }
+        let vars = [("SHADER", true), ("_x1", true), ("a_B", true), ("", false), ("1x", false), ("a-b", false), ("a.b", false), ("é", false)];
+        for (name, ok) in vars {
+            assert_eq!(is_var_name(name), ok, "{:?}", name);
+        }
+
+        let session = Rc::new(RefCell::new(Session::default()));
+        let alias = run(&session, "alias 'a b=ls' ok=ls");
+        assert_eq!((alias.status, alias.stderr.as_str()), (1, "alias: 'a b': invalid alias name"));
+        let export = run(&session, "export 1x=2 X=1");
+        assert_eq!((export.status, export.stderr.as_str()), (1, "export: '1x': not a valid identifier"));
+        // The good names beside the bad ones are still set.
+        assert_eq!(session.borrow().alias("ok"), Some("ls"));
+        assert_eq!(session.borrow().var("X"), Some("1"));
+    }
+
+    #[test]
+    fn unalias_unset_and_env() {
+        let session = Rc::new(RefCell::new(Session::default()));
+        run(&session, "alias a=1 b=2 c=3");
+        run(&session, "export B=2 A=1");
+        assert_eq!(run(&session, "env").stdout, "A=1\nB=2");
+        assert_eq!(run(&session, "env -x").status, 1);
+
+        let missing = run(&session, "unalias a z");
+        assert_eq!((missing.status, missing.stderr.as_str()), (1, "unalias: z: not found"));
+        assert_eq!(run(&session, "alias").stdout, "alias b='2'\nalias c='3'");
+        run(&session, "unalias -a");
+        assert_eq!(run(&session, "alias").stdout, "");
+        assert_eq!(run(&session, "unalias").status, 1);
+
+        assert_eq!(run(&session, "unset A NOPE").status, 0);
+        assert_eq!(run(&session, "env").stdout, "B=2");
+        assert_eq!(run(&session, "unset").status, 1);
+        // `export NAME` keeps its value.
+        run(&session, "export B");
+        assert_eq!(session.borrow().var("B"), Some("2"));
+    }
+
+    #[test]
+    fn history_lists_the_last_n_and_clears() {
+        let session = Rc::new(RefCell::new(Session::default()));
+        for line in ["ls", "cat resume", "whoami"] {
+            session.borrow_mut().push(line);
+        }
+        let cases = [
+            ("history", "    1  ls\n    2  cat resume\n    3  whoami"),
+            ("history 2", "    2  cat resume\n    3  whoami"),
+            ("history 9", "    1  ls\n    2  cat resume\n    3  whoami"),
+            ("history 0", ""),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(run(&session, line).stdout, expected, "{}", line);
+        }
+        for line in ["history x", "history -1", "history 1 2"] {
+            assert_eq!(run(&session, line).stderr, "usage: history [-c] [N]", "{}", line);
+        }
+
+        assert_eq!(run(&session, "history -c").status, 0);
+        assert_eq!(run(&session, "history").stdout, "");
+        // Numbers carry on after a clear, so `!N` never means something else.
+        session.borrow_mut().push("date");
+        assert_eq!(run(&session, "history").stdout, "    4  date");
+    }
+}
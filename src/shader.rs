@@ -0,0 +1,136 @@
+//! The shaders build.rs embeds, and their tunable parameters.
+//!
+//! Each `src/shaders/*.wgsl` but `vs.wgsl` starts with a [header] naming the
+//! shader and declaring its parameters:
+//!
+//! ```text
+//! // @param speed 1.0 0.0..4.0 How fast the pattern drifts
+//! ```
+//!
+//! The shader reads that back with the generated `param_speed()`. Values are
+//! packed in declaration order into `uniforms.params`, five vec4s, so they
+//! can change every frame without recompiling anything. The [uniforms]
+//! struct itself is generated too.
+
+use std::borrow::Cow;
+
+pub mod graph;
+pub mod header;
+pub mod load;
+pub mod preprocess;
+pub mod transition;
+pub mod uniforms;
+pub mod validate;
+
+pub use graph::{Input, Pass};
+pub use header::{accessors, generated, Param, MAX_PARAMS};
+
+/// A shader with its header parsed: embedded by build.rs, or loaded at
+/// runtime by [`load`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderInfo {
+    /// The file name without `.wgsl`, as typed in `shader NAME`.
+    pub id: Cow<'static, str>,
+    /// `@name`, or the id.
+    pub name: Cow<'static, str>,
+    pub description: Cow<'static, str>,
+    pub author: Cow<'static, str>,
+    pub tags: Cow<'static, [Cow<'static, str>]>,
+    pub hidden: bool,
+    pub default: bool,
+    pub params: Cow<'static, [Param]>,
+    /// Its [graph] passes; empty for a single-pass shader.
+    pub passes: Cow<'static, [Pass]>,
+    /// The file as written.
+    pub source: Cow<'static, str>,
+    /// The file with its [preprocess] directives resolved, as compiled.
+    pub expanded: Cow<'static, str>,
+}
+
+impl ShaderInfo {
+    /// The info for a shader read at runtime.
+    pub fn new(id: &str, header: header::Header, source: &str, expanded: &str) -> Self {
+        ShaderInfo {
+            name: header.name.unwrap_or_else(|| id.to_string()).into(),
+            id: id.to_string().into(),
+            description: header.description.into(),
+            author: header.author.into(),
+            tags: header.tags.into_iter().map(Cow::Owned).collect(),
+            hidden: header.hidden,
+            default: header.default,
+            params: header.params.into(),
+            passes: header.passes.into(),
+            source: source.to_string().into(),
+            expanded: expanded.to_string().into(),
+        }
+    }
+
+    /// What the renderer compiles: the expanded source and the generated code.
+    pub fn wgsl(&self) -> String {
+        format!("{}\n{}", self.expanded, generated(&self.params, &self.passes))
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/shaders.rs"));
+
+/// The shader shown on load: the one marked `@default`.
+pub fn default_shader() -> Option<&'static ShaderInfo> {
+    SHADERS.iter().find(|info| info.default)
+}
+
+/// A shader's parameters and their current values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Params {
+    params: Vec<Param>,
+    values: Vec<f32>,
+}
+
+impl Params {
+    pub fn new(params: &[Param]) -> Self {
+        let params = params.to_vec();
+        let values = params.iter().map(|p| p.default).collect();
+        Params { params, values }
+    }
+
+    /// Each parameter with its current value, in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Param, f32)> {
+        self.params.iter().zip(self.values.iter().copied())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.params.iter().map(|p| p.name.to_string()).collect()
+    }
+
+    pub fn set(&mut self, name: &str, value: f32) -> Result<(), String> {
+        let Some(i) = self.params.iter().position(|p| p.name == name) else {
+            let names = self.names();
+            return Err(if names.is_empty() {
+                format!("unknown parameter '{}': this shader has none", name)
+            } else {
+                format!("unknown parameter '{}' (try {})", name, names.join(", "))
+            });
+        };
+        let param = &self.params[i];
+        if !(param.min..=param.max).contains(&value) {
+            return Err(format!("{} must be between {} and {}", name, param.min, param.max));
+        }
+        self.values[i] = value;
+        Ok(())
+    }
+
+    /// Puts every parameter back to its default.
+    pub fn reset(&mut self) {
+        for (value, param) in self.values.iter_mut().zip(&self.params) {
+            *value = param.default;
+        }
+    }
+
+    /// The values laid out like `uniforms.params`.
+    pub fn packed(&self) -> [[f32; 4]; uniforms::PARAM_VEC4S] {
+        let mut packed = [[0.0; 4]; uniforms::PARAM_VEC4S];
+        for (i, value) in self.values.iter().enumerate() {
+            packed[i / 4][i % 4] = *value;
+        }
+        packed
+    }
+}
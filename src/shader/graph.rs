@@ -0,0 +1,320 @@
+//! Multi-pass shaders. A shader can split its work into passes, each a
+//! `@fragment` function drawing into its own texture, declared in the
+//! [header](super::header) in the order they run:
+//!
+//! ```text
+//! // @pass trail 0.5 trail.previous, noise
+//! // @pass main 1.0 trail
+//! ```
+//!
+//! That's the entry point, the pass's size as a fraction of the screen, and
+//! what it reads: a pass that ran before it this frame, any pass's output
+//! from the previous frame, or a tiling `noise` texture. The last pass draws
+//! to the screen. For each input the shader gets `input_<name>(uv)`, with
+//! `.previous` spelled `_previous`, and for each pass `resolution_<pass>()`.
+//! A shader without passes is one pass, `main`, reading nothing.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// The most passes a shader can have.
+pub const MAX_PASSES: usize = 8;
+
+/// The width and height of the `noise` texture.
+pub const NOISE_SIZE: u32 = 256;
+
+/// What a pass reads.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    /// A pass's output from earlier this frame.
+    Pass(Cow<'static, str>),
+    /// A pass's output from the previous frame; black on the first.
+    Previous(Cow<'static, str>),
+    Noise,
+}
+
+impl Input {
+    fn parse(text: &str) -> Result<Input, String> {
+        let input = match text.strip_suffix(".previous") {
+            Some(pass) => Input::Previous(pass.to_string().into()),
+            None if text == "noise" => Input::Noise,
+            None => Input::Pass(text.to_string().into()),
+        };
+        match &input {
+            Input::Pass(pass) | Input::Previous(pass) if !identifier(pass) => Err(format!("'{}' is not a pass", text)),
+            _ => Ok(input),
+        }
+    }
+
+    /// The pass it reads, if it reads one.
+    pub fn pass(&self) -> Option<&str> {
+        match self {
+            Input::Pass(pass) | Input::Previous(pass) => Some(pass),
+            Input::Noise => None,
+        }
+    }
+
+    /// What it's called in WGSL, after `input_`.
+    pub fn wgsl_name(&self) -> String {
+        match self {
+            Input::Pass(pass) => pass.to_string(),
+            Input::Previous(pass) => format!("{}_previous", pass),
+            Input::Noise => "noise".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Pass(pass) => f.write_str(pass),
+            Input::Previous(pass) => write!(f, "{}.previous", pass),
+            Input::Noise => f.write_str("noise"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pass {
+    /// Its `@fragment` function.
+    pub name: Cow<'static, str>,
+    /// Its size as a fraction of the screen's.
+    pub scale: f32,
+    pub inputs: Cow<'static, [Input]>,
+}
+
+/// The pass of a shader that declares none.
+pub fn single() -> Pass {
+    Pass { name: Cow::Borrowed("main"), scale: 1.0, inputs: Cow::Borrowed(&[]) }
+}
+
+/// The passes a shader runs: `passes`, or [`single`] if there are none.
+pub fn entries(passes: &[Pass]) -> Vec<Pass> {
+    if passes.is_empty() {
+        vec![single()]
+    } else {
+        passes.to_vec()
+    }
+}
+
+fn identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parses `NAME SCALE [INPUT, INPUT...]`.
+pub fn pass(declaration: &str) -> Result<Pass, String> {
+    let mut words = declaration.splitn(3, char::is_whitespace).filter(|w| !w.is_empty());
+    let (Some(name), Some(scale)) = (words.next(), words.next()) else {
+        return Err("expected '// @pass NAME SCALE [INPUT, ...]'".to_string());
+    };
+    if !identifier(name) || name == "noise" {
+        return Err(format!("'{}' is not a valid pass name", name));
+    }
+    let scale = scale
+        .parse::<f32>()
+        .ok()
+        .filter(|s| *s > 0.0 && *s <= 1.0)
+        .ok_or_else(|| format!("the scale must be a number in (0, 1], not '{}'", scale))?;
+    let mut inputs = Vec::new();
+    for text in words.next().unwrap_or_default().split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let input = Input::parse(text)?;
+        if inputs.contains(&input) {
+            return Err(format!("{} is read twice", input));
+        }
+        inputs.push(input);
+    }
+    Ok(Pass { name: name.to_string().into(), scale, inputs: inputs.into() })
+}
+
+/// Checks that `passes` fit together, or says which one doesn't and why.
+pub fn check(passes: &[Pass]) -> Result<(), (usize, String)> {
+    let last = passes.len().saturating_sub(1);
+    for (i, pass) in passes.iter().enumerate() {
+        if passes[..i].iter().any(|p| p.name == pass.name) {
+            return Err((i, format!("pass '{}' is declared twice", pass.name)));
+        }
+        if i == last && pass.scale != 1.0 {
+            return Err((i, "the last pass draws to the screen, so its scale must be 1".to_string()));
+        }
+        for input in pass.inputs.iter() {
+            let Some(name) = input.pass() else {
+                continue;
+            };
+            let Some(source) = passes.iter().position(|p| p.name == name) else {
+                return Err((i, format!("there's no pass '{}'", name)));
+            };
+            if source == last {
+                return Err((i, format!("'{}' draws to the screen, so no pass can read it", name)));
+            }
+            if matches!(input, Input::Pass(_)) && source == i {
+                return Err((i, format!("a pass can't read what it's drawing; read {}.previous instead", name)));
+            }
+            if matches!(input, Input::Pass(_)) && source >= i {
+                return Err((i, format!("'{}' hasn't run yet when '{}' does; read {}.previous instead", name, pass.name, name)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every input any pass reads, in order of first use. Input `i` is bound
+/// at `@group(1) @binding(i + 1)`, after the sampler.
+pub fn bindings(passes: &[Pass]) -> Vec<Input> {
+    let mut inputs: Vec<Input> = Vec::new();
+    for input in passes.iter().flat_map(|pass| pass.inputs.iter()) {
+        if !inputs.contains(input) {
+            inputs.push(input.clone());
+        }
+    }
+    inputs
+}
+
+/// Whether some pass reads `pass`'s previous output, so it needs two textures.
+pub fn has_feedback(passes: &[Pass], pass: &str) -> bool {
+    passes.iter().flat_map(|p| p.inputs.iter()).any(|input| matches!(input, Input::Previous(p) if p == pass))
+}
+
+/// The size of a texture at `scale` of `screen`, as `resolution_<pass>()`
+/// computes it.
+pub fn size(scale: f32, screen: (u32, u32)) -> (u32, u32) {
+    let side = |n: u32| ((n as f32 * scale).floor() as u32).max(1);
+    (side(screen.0), side(screen.1))
+}
+
+/// WGSL for the inputs and resolutions of `passes`, to compile along with
+/// the shader. Empty for a single-pass shader.
+pub fn wgsl(passes: &[Pass]) -> String {
+    if passes.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("@group(1) @binding(0) var input_sampler: sampler;\n");
+    for (i, input) in bindings(passes).iter().enumerate() {
+        let name = input.wgsl_name();
+        // Noise tiles; everything else is clamped at the edges.
+        let uv = if *input == Input::Noise { "fract(uv)" } else { "uv" };
+        out.push_str(&format!(
+            "@group(1) @binding({}) var input_{}_texture: texture_2d<f32>;\n\
+             fn input_{}(uv: vec2<f32>) -> vec4<f32> {{ return textureSampleLevel(input_{}_texture, input_sampler, {}, 0.0); }}\n",
+            i + 1,
+            name,
+            name,
+            name,
+            uv
+        ));
+    }
+    for pass in passes {
+        out.push_str(&format!(
+            "fn resolution_{}() -> vec2<f32> {{ return max(floor(uniforms.resolution * {:?}), vec2<f32>(1.0)); }}\n",
+            pass.name, pass.scale
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `declarations`, one `@pass` each, parsed.
+    fn passes(declarations: &[&str]) -> Vec<Pass> {
+        declarations.iter().map(|d| pass(d).unwrap()).collect()
+    }
+
+    #[test]
+    fn parses_passes() {
+        let blur = pass("blur 0.5 trail.previous, noise,scene").unwrap();
+        assert_eq!(blur.name, "blur");
+        assert_eq!(blur.scale, 0.5);
+        assert_eq!(*blur.inputs, [Input::Previous("trail".into()), Input::Noise, Input::Pass("scene".into())]);
+        assert_eq!(pass("main 1"), Ok(Pass { name: "main".into(), scale: 1.0, inputs: Vec::new().into() }));
+        let text: Vec<String> = blur.inputs.iter().map(|i| format!("{} {}", i, i.wgsl_name())).collect();
+        assert_eq!(text, ["trail.previous trail_previous", "noise noise", "scene scene"]);
+    }
+
+    #[test]
+    fn reports_bad_passes() {
+        let cases = [
+            ("main", "expected '// @pass NAME SCALE [INPUT, ...]'"),
+            ("2d 1.0", "'2d' is not a valid pass name"),
+            ("noise 1.0", "'noise' is not a valid pass name"),
+            ("main 0", "the scale must be a number in (0, 1], not '0'"),
+            ("main 1.5", "the scale must be a number in (0, 1], not '1.5'"),
+            ("main big", "the scale must be a number in (0, 1], not 'big'"),
+            ("main 1.0 a-b", "'a-b' is not a pass"),
+            ("main 1.0 .previous", "'.previous' is not a pass"),
+            ("main 1.0 a, noise, a", "a is read twice"),
+        ];
+        for (declaration, message) in cases {
+            assert_eq!(pass(declaration), Err(message.to_string()), "{}", declaration);
+        }
+    }
+
+    #[test]
+    fn checks_how_passes_fit_together() {
+        let ok: [&[&str]; 4] = [
+            &[],
+            &["main 1.0"],
+            &["trail 0.5 trail.previous, noise", "main 1.0 trail"],
+            &["a 1.0 b.previous", "b 1.0 a", "main 1.0 a, b, b.previous"],
+        ];
+        for declarations in ok {
+            assert_eq!(check(&passes(declarations)), Ok(()), "{:?}", declarations);
+        }
+        let errors: [(&[&str], usize, &str); 7] = [
+            (&["a 0.5", "a 1.0"], 1, "pass 'a' is declared twice"),
+            (&["a 0.5", "main 0.5"], 1, "the last pass draws to the screen, so its scale must be 1"),
+            (&["main 1.0 blur"], 0, "there's no pass 'blur'"),
+            (&["a 1.0 main.previous", "main 1.0"], 0, "'main' draws to the screen, so no pass can read it"),
+            (&["a 1.0 a", "main 1.0"], 0, "a pass can't read what it's drawing; read a.previous instead"),
+            // Two passes reading each other this frame would be a cycle.
+            (&["a 1.0 b", "b 1.0 a", "main 1.0"], 0, "'b' hasn't run yet when 'a' does; read b.previous instead"),
+            (&["a 1.0", "b 1.0 c", "c 1.0 b", "main 1.0 c"], 1, "'c' hasn't run yet when 'b' does; read c.previous instead"),
+        ];
+        for (declarations, index, message) in errors {
+            assert_eq!(check(&passes(declarations)), Err((index, message.to_string())), "{:?}", declarations);
+        }
+    }
+
+    #[test]
+    fn binds_each_input_once_in_order_of_use() {
+        let graph = passes(&["trail 0.5 trail.previous, noise", "main 1.0 trail, noise, trail.previous"]);
+        assert_eq!(bindings(&graph), [Input::Previous("trail".into()), Input::Noise, Input::Pass("trail".into())]);
+        assert!(has_feedback(&graph, "trail"));
+        assert!(!has_feedback(&graph, "main"));
+        assert_eq!(entries(&[]), [single()]);
+        assert_eq!(entries(&graph), graph);
+    }
+
+    #[test]
+    fn sizes_textures_at_least_one_texel() {
+        assert_eq!(size(1.0, (800, 600)), (800, 600));
+        assert_eq!(size(0.5, (801, 600)), (400, 300));
+        assert_eq!(size(0.001, (800, 600)), (1, 1));
+    }
+
+    #[test]
+    fn generates_inputs_and_resolutions() {
+        assert_eq!(wgsl(&[]), "");
+        let expected = "@group(1) @binding(0) var input_sampler: sampler;
+@group(1) @binding(1) var input_trail_previous_texture: texture_2d<f32>;
+fn input_trail_previous(uv: vec2<f32>) -> vec4<f32> { return textureSampleLevel(input_trail_previous_texture, input_sampler, uv, 0.0); }
+@group(1) @binding(2) var input_noise_texture: texture_2d<f32>;
+fn input_noise(uv: vec2<f32>) -> vec4<f32> { return textureSampleLevel(input_noise_texture, input_sampler, fract(uv), 0.0); }
+@group(1) @binding(3) var input_trail_texture: texture_2d<f32>;
+fn input_trail(uv: vec2<f32>) -> vec4<f32> { return textureSampleLevel(input_trail_texture, input_sampler, uv, 0.0); }
+fn resolution_trail() -> vec2<f32> { return max(floor(uniforms.resolution * 0.5), vec2<f32>(1.0)); }
+fn resolution_main() -> vec2<f32> { return max(floor(uniforms.resolution * 1.0), vec2<f32>(1.0)); }
+";
+        let graph = passes(&["trail 0.5 trail.previous, noise", "main 1.0 trail"]);
+        assert_eq!(wgsl(&graph), expected);
+
+        // It compiles along with the uniforms and a shader that uses it.
+        let shader = "@fragment fn trail() -> @location(0) vec4<f32> { return input_trail_previous(vec2<f32>(0.5)) + input_noise(resolution_trail()); }
+@fragment fn main() -> @location(0) vec4<f32> { return input_trail(vec2<f32>(0.5)); }
+";
+        let source = format!("{}{}", shader, super::super::header::generated(&[], &graph));
+        if let Err(e) = super::super::validate::module("trail.wgsl", &source) {
+            panic!("line {}: {}", e.line, e.message);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! The comment header at the top of each shader.
+//!
+//! ```text
+//! // @name Gel
+//! // @description Slow layered noise, like looking through frosted glass
+//! // @author Jane Doe
+//! // @tags noise, monochrome
+//! // @default
+//! // @param speed 1.0 0.0..4.0 How fast the pattern drifts
+//! // @pass main 1.0
+//! ```
+//!
+//! The header is the run of `//` lines the file starts with; `@hidden`
+//! keeps a shader out of listings, and `@default` makes it the one shown on
+//! load. `@pass` is for [multi-pass](super::graph) shaders.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use super::graph::{self, Pass};
+
+/// How many parameters fit in `uniforms.params`.
+pub const MAX_PARAMS: usize = 20;
+
+/// Words `shader` takes where a shader name would go, so no shader can be
+/// called one.
+pub const SUBCOMMANDS: [&str; 4] = ["params", "set", "reset", "edit"];
+
+/// Checks `id`, a shader's file name without `.wgsl`, which is what
+/// `shader NAME` takes.
+pub fn check_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        Err(format!("'{}' is not a valid shader name", id))
+    } else if SUBCOMMANDS.contains(&id) {
+        Err(format!("'{}' is a 'shader' subcommand, so it can't name a shader", id))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: Cow<'static, str>,
+    pub default: f32,
+    pub min: f32,
+    pub max: f32,
+    pub description: Cow<'static, str>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Header {
+    /// `@name`, the display name.
+    pub name: Option<String>,
+    pub description: String,
+    pub author: String,
+    pub tags: Vec<String>,
+    pub hidden: bool,
+    pub default: bool,
+    pub params: Vec<Param>,
+    /// `@pass`es in the order they run; empty for a single-pass shader.
+    pub passes: Vec<Pass>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderError {
+    /// 1-based line in the shader.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Reads the header of `source`. Errors name the line.
+pub fn parse(source: &str) -> Result<Header, HeaderError> {
+    let mut header = Header::default();
+    let mut seen: Vec<&str> = Vec::new();
+    // The line of each pass, for errors about how they fit together.
+    let mut pass_lines = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(comment) = line.strip_prefix("//") else {
+            break;
+        };
+        let Some(field) = comment.trim_start().strip_prefix('@') else {
+            continue;
+        };
+        let error = |message: String| HeaderError { line: i + 1, message };
+        let (key, value) = field.split_once(char::is_whitespace).unwrap_or((field, ""));
+        let value = value.trim();
+        if key != "param" && key != "pass" {
+            if seen.contains(&key) {
+                return Err(error(format!("@{} is given twice", key)));
+            }
+            seen.push(key);
+        }
+        match key {
+            "name" | "description" | "author" if value.is_empty() => return Err(error(format!("@{} needs a value", key))),
+            "name" => header.name = Some(value.to_string()),
+            "description" => header.description = value.to_string(),
+            "author" => header.author = value.to_string(),
+            "tags" => header.tags = value.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect(),
+            "hidden" | "default" if !value.is_empty() => return Err(error(format!("@{} takes no value", key))),
+            "hidden" => header.hidden = true,
+            "default" => header.default = true,
+            "param" => {
+                let param = param(value).map_err(error)?;
+                if header.params.iter().any(|p| p.name == param.name) {
+                    return Err(error(format!("'{}' is declared twice", param.name)));
+                }
+                if header.params.len() == MAX_PARAMS {
+                    return Err(error(format!("only {} parameters fit", MAX_PARAMS)));
+                }
+                header.params.push(param);
+            }
+            "pass" => {
+                if header.passes.len() == graph::MAX_PASSES {
+                    return Err(error(format!("only {} passes fit", graph::MAX_PASSES)));
+                }
+                header.passes.push(graph::pass(value).map_err(error)?);
+                pass_lines.push(i + 1);
+            }
+            _ => return Err(error(format!("unknown field @{}", key))),
+        }
+    }
+    graph::check(&header.passes).map_err(|(i, message)| HeaderError { line: pass_lines[i], message })?;
+    if header.hidden && header.default {
+        return Err(HeaderError { line: 1, message: "a @hidden shader can't be the @default".to_string() });
+    }
+    Ok(header)
+}
+
+/// Parses `NAME DEFAULT MIN..MAX DESCRIPTION`.
+fn param(declaration: &str) -> Result<Param, String> {
+    let mut words = declaration.splitn(4, char::is_whitespace).filter(|w| !w.is_empty());
+    let usage = "expected '// @param NAME DEFAULT MIN..MAX DESCRIPTION'";
+    let (Some(name), Some(default), Some(range)) = (words.next(), words.next(), words.next()) else {
+        return Err(usage.to_string());
+    };
+    let description = words.next().unwrap_or_default().trim().to_string();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("'{}' is not a valid parameter name", name));
+    }
+    let number = |text: &str| text.parse::<f32>().ok().filter(|n| n.is_finite()).ok_or_else(|| format!("'{}' is not a number", text));
+    let default = number(default)?;
+    let Some((min, max)) = range.split_once("..") else {
+        return Err(usage.to_string());
+    };
+    let (min, max) = (number(min)?, number(max)?);
+    if min >= max {
+        return Err(format!("empty range {}..{}", min, max));
+    }
+    if !(min..=max).contains(&default) {
+        return Err(format!("default {} is outside {}..{}", default, min, max));
+    }
+    Ok(Param { name: name.to_string().into(), default, min, max, description: description.into() })
+}
+
+/// What gets appended to a shader before compiling it: the uniforms, the
+/// parameter accessors and the passes' inputs.
+pub fn generated(params: &[Param], passes: &[Pass]) -> String {
+    format!("{}\n{}{}", super::uniforms::wgsl(), accessors(params), graph::wgsl(passes))
+}
+
+/// WGSL for a `param_<name>()` function per parameter, to compile along with the shader.
+pub fn accessors(params: &[Param]) -> String {
+    let mut wgsl = String::new();
+    for (i, param) in params.iter().enumerate() {
+        wgsl.push_str(&format!(
+            "fn param_{}() -> f32 {{ return uniforms.params[{}u].{}; }}\n",
+            param.name,
+            i / 4,
+            ["x", "y", "z", "w"][i % 4]
+        ));
+    }
+    wgsl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_field() {
+        let source = "// @name Gel
+// @description Slow layered noise
+// A plain comment in between.
+// @author Jane Doe
+// @tags noise, , monochrome
+// @default
+// @param speed 1.0 0.0..4.0 How fast the pattern drifts
+// @param scale -1 -2..2
+@fragment fn main() {}
+// @name Not the header any more";
+        let header = parse(source).unwrap();
+        assert_eq!(header.name.as_deref(), Some("Gel"));
+        assert_eq!(header.description, "Slow layered noise");
+        assert_eq!(header.author, "Jane Doe");
+        assert_eq!(header.tags, ["noise", "monochrome"]);
+        assert!(header.default && !header.hidden);
+        assert_eq!(
+            header.params,
+            [
+                Param { name: "speed".into(), default: 1.0, min: 0.0, max: 4.0, description: "How fast the pattern drifts".into() },
+                Param { name: "scale".into(), default: -1.0, min: -2.0, max: 2.0, description: "".into() },
+            ]
+        );
+        assert!(header.passes.is_empty());
+        assert_eq!(parse("fn main() {}"), Ok(Header::default()));
+    }
+
+    #[test]
+    fn reports_bad_headers_with_their_line() {
+        let cases = [
+            ("// @name A\n// @name B", 2, "@name is given twice"),
+            ("// @default\n// @hidden\n// @default", 3, "@default is given twice"),
+            ("// @name", 1, "@name needs a value"),
+            ("// @default yes", 1, "@default takes no value"),
+            ("// @colour red", 1, "unknown field @colour"),
+            ("// @param speed", 1, "expected '// @param NAME DEFAULT MIN..MAX DESCRIPTION'"),
+            ("// @param speed 1 0-4", 1, "expected '// @param NAME DEFAULT MIN..MAX DESCRIPTION'"),
+            ("// @param speed fast 0..4", 1, "'fast' is not a number"),
+            ("// @param speed 1 0..inf", 1, "'inf' is not a number"),
+            ("// @param speed 1 4..0", 1, "empty range 4..0"),
+            ("// @param speed 1 1..1", 1, "empty range 1..1"),
+            ("// @param speed 5 0..4", 1, "default 5 is outside 0..4"),
+            ("// @param 2fast 1 0..4", 1, "'2fast' is not a valid parameter name"),
+            ("\n// @param a 1 0..4\n// @param a 2 0..4", 3, "'a' is declared twice"),
+            ("// @hidden\n// @default", 1, "a @hidden shader can't be the @default"),
+        ];
+        for (source, line, message) in cases {
+            assert_eq!(parse(source), Err(HeaderError { line, message: message.to_string() }), "{}", source);
+        }
+    }
+
+    #[test]
+    fn fits_at_most_max_params() {
+        let params = |n: usize| (0..n).map(|i| format!("// @param p{} 0 0..1", i)).collect::<Vec<String>>().join("\n");
+        assert_eq!(parse(&params(MAX_PARAMS)).unwrap().params.len(), MAX_PARAMS);
+        let error = parse(&params(MAX_PARAMS + 1)).unwrap_err();
+        assert_eq!(error, HeaderError { line: MAX_PARAMS + 1, message: format!("only {} parameters fit", MAX_PARAMS) });
+    }
+
+    #[test]
+    fn checks_shader_ids() {
+        for id in ["fire", "gel-2", "my_shader"] {
+            assert_eq!(check_id(id), Ok(()), "{}", id);
+        }
+        assert_eq!(check_id(""), Err("'' is not a valid shader name".to_string()));
+        assert_eq!(check_id("a b"), Err("'a b' is not a valid shader name".to_string()));
+        assert_eq!(check_id("lib/noise"), Err("'lib/noise' is not a valid shader name".to_string()));
+        for id in SUBCOMMANDS {
+            assert_eq!(check_id(id), Err(format!("'{}' is a 'shader' subcommand, so it can't name a shader", id)));
+        }
+    }
+
+    #[test]
+    fn accessors_read_packed_params() {
+        let params = parse("// @param a 0 0..1\n// @param b 0 0..1\n// @param c 0 0..1\n// @param d 0 0..1\n// @param e 0 0..1").unwrap().params;
+        let wgsl = accessors(&params);
+        assert!(wgsl.starts_with("fn param_a() -> f32 { return uniforms.params[0u].x; }\n"), "{}", wgsl);
+        assert!(wgsl.ends_with("fn param_e() -> f32 { return uniforms.params[1u].x; }\n"), "{}", wgsl);
+    }
+}
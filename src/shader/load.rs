@@ -0,0 +1,148 @@
+//! Shaders read at runtime, for hot reload and the editor: the same header,
+//! preprocessor and naga checks build.rs runs, reported as [`Diagnostic`]s
+//! instead of build errors.
+
+use serde::Serialize;
+use std::fmt;
+
+use super::{graph, header, preprocess, validate, ShaderInfo, LIBRARY, VERTEX_SHADER};
+
+/// A problem with a shader, for the page to show.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The file it's in, like `gel.wgsl` or `lib/noise.wgsl`.
+    pub file: String,
+    /// 1-based, or `None` if it isn't about one line.
+    pub line: Option<usize>,
+    pub message: String,
+    /// naga's rendering of the error with the source around it, if it has one.
+    pub report: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(file: &str, line: Option<usize>, message: impl Into<String>) -> Self {
+        Diagnostic { file: file.to_string(), line, message: message.into(), report: None }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file, line, self.message),
+            None => write!(f, "{}: {}", self.file, self.message),
+        }
+    }
+}
+
+/// A shader that passed every check.
+#[derive(Debug, Clone)]
+pub struct Prepared {
+    pub info: ShaderInfo,
+    /// What to compile: the expanded source and the generated code.
+    pub wgsl: String,
+    /// Where each line of `wgsl` before the generated code came from.
+    pub lines: Vec<preprocess::Origin>,
+}
+
+impl Prepared {
+    /// An error the GPU reported compiling `wgsl`, at the line it names if
+    /// it names one, like `:12:5` or `wgsl:12:5`.
+    pub fn gpu_error(&self, message: &str) -> Diagnostic {
+        let file = format!("{}.wgsl", self.info.id);
+        let origin = error_line(message).and_then(|line| self.lines.get(line.checked_sub(1)?));
+        match origin {
+            Some(origin) => Diagnostic::new(&origin.file, Some(origin.line), message),
+            None => Diagnostic::new(&file, None, message),
+        }
+    }
+}
+
+/// The line of the first `:line:column` in `message`.
+fn error_line(message: &str) -> Option<usize> {
+    let parts: Vec<&str> = message.split(':').collect();
+    let number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    // Only what follows a `:` can be the line, and the column may run into the message.
+    parts[1..].windows(2).find(|w| number(w[0]) && w[1].starts_with(|c: char| c.is_ascii_digit())).and_then(|w| w[0].parse().ok())
+}
+
+/// The `#include`able files build.rs embedded, by path.
+pub fn embedded_library(path: &str) -> Option<String> {
+    LIBRARY.iter().find(|(name, _)| *name == path).map(|(_, source)| source.to_string())
+}
+
+/// Checks `source` as shader `id`, reading `#include`d files with `library`.
+pub fn prepare(id: &str, source: &str, library: &dyn Fn(&str) -> Option<String>) -> Result<Prepared, Vec<Diagnostic>> {
+    let file = format!("{}.wgsl", id);
+    header::check_id(id).map_err(|message| vec![Diagnostic::new(&file, None, message)])?;
+    let header = header::parse(source).map_err(|e| vec![Diagnostic::new(&file, Some(e.line), e.message)])?;
+
+    let mut load = |name: &str| {
+        if name == file {
+            Ok(source.to_string())
+        } else {
+            library(name).ok_or_else(|| "no such file".to_string())
+        }
+    };
+    let expanded = preprocess::expand(&file, &mut load).map_err(|e| vec![Diagnostic::new(&e.file, Some(e.line), e.message)])?;
+
+    let info = ShaderInfo::new(id, header, source, &expanded.text);
+    let wgsl = info.wgsl();
+    // Where an error in `wgsl` really is: in a file, or in the generated code.
+    let locate = |error: validate::Error| {
+        let mut diagnostic = match expanded.origin(error.line) {
+            Some(origin) => Diagnostic::new(&origin.file, Some(origin.line), error.message),
+            None => Diagnostic::new(&file, None, format!("in generated code: {}", error.message)),
+        };
+        diagnostic.report = error.report;
+        diagnostic
+    };
+    let module = validate::module(&format!("{} (expanded)", file), &wgsl).map_err(|e| vec![locate(e)])?;
+    let vertex = validate::module("vs.wgsl", VERTEX_SHADER).map_err(|e| vec![Diagnostic::new("vs.wgsl", Some(e.line), e.message)])?;
+    let errors: Vec<Diagnostic> = graph::entries(&info.passes)
+        .iter()
+        .flat_map(|pass| validate::interface(&vertex, &module, &wgsl, &pass.name))
+        .chain(validate::resources(&module, &wgsl))
+        .chain(validate::inputs(&module, &info.passes, &wgsl))
+        .map(locate)
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(Prepared { info, wgsl, lines: expanded.lines })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_line_gpu_errors_name() {
+        assert_eq!(error_line("Shader 'fire' parsing error: expected ';'\n  ┌─ wgsl:12:5\n"), Some(12));
+        assert_eq!(error_line(":3:14 unknown identifier"), Some(3));
+        assert_eq!(error_line("at 10:20:30: nope"), Some(20));
+        assert_eq!(error_line("error: 12:x"), None);
+        assert_eq!(error_line("no position"), None);
+    }
+
+    #[test]
+    fn prepares_every_embedded_shader() {
+        for info in crate::shader::SHADERS.iter() {
+            if let Err(errors) = prepare(&info.id, &info.source, &embedded_library) {
+                panic!("{}: {:?}", info.id, errors);
+            }
+        }
+    }
+
+    #[test]
+    fn reports_errors_in_the_file_they_are_in() {
+        let library = |path: &str| (path == "lib/broken.wgsl").then(|| "// helpers\nfn broken() -> f32 { return 1u; }".to_string());
+        let source = "// @name Broken\n#include \"lib/broken.wgsl\"\n@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(broken()); }";
+        let errors = prepare("broken", source, &library).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].file.as_str(), errors[0].line), ("lib/broken.wgsl", Some(2)));
+
+        let source = "// @name Broken\n\n@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(1.0) }";
+        let errors = prepare("broken", source, &library).unwrap_err();
+        assert_eq!((errors[0].file.as_str(), errors[0].line), ("broken.wgsl", Some(3)));
+    }
+}
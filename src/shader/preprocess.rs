@@ -0,0 +1,301 @@
+//! A small C-style preprocessor, so shaders can share code:
+//!
+//! ```text
+//! #define NOISE_OCTAVES 4
+//! #include "lib/noise.wgsl"
+//! #ifdef NAME / #ifndef NAME / #else / #endif
+//! #undef NAME
+//! ```
+//!
+//! Include paths are relative to `src/shaders`, and each file is included
+//! at most once, so libraries need no guards. A defined name is replaced in
+//! the lines after it, outside `//` comments. Every line of the output
+//! remembers where it came from, so errors can point at the original file.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Where a line of the output came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    pub file: String,
+    /// 1-based.
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expanded {
+    pub text: String,
+    /// The origin of each line of `text`.
+    pub lines: Vec<Origin>,
+}
+
+impl Expanded {
+    /// Where 1-based `line` of the output came from, if it's in the output.
+    pub fn origin(&self, line: usize) -> Option<&Origin> {
+        self.lines.get(line.checked_sub(1)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.message)
+    }
+}
+
+/// Expands `file`, reading it and anything it includes with `load`.
+pub fn expand(file: &str, load: &mut dyn FnMut(&str) -> Result<String, String>) -> Result<Expanded, Error> {
+    let mut preprocessor = Preprocessor { load, defines: BTreeMap::new(), including: Vec::new(), included: Vec::new(), out: Expanded::default() };
+    preprocessor.file(file, None)?;
+    Ok(preprocessor.out)
+}
+
+/// An open `#ifdef` or `#ifndef`.
+struct Conditional {
+    line: usize,
+    /// Whether the lines before any `#else` are kept, ignoring enclosing blocks.
+    condition: bool,
+    /// Whether the enclosing blocks keep their lines.
+    outer: bool,
+    in_else: bool,
+}
+
+impl Conditional {
+    fn active(&self) -> bool {
+        self.outer && self.condition != self.in_else
+    }
+}
+
+struct Preprocessor<'a> {
+    load: &'a mut dyn FnMut(&str) -> Result<String, String>,
+    defines: BTreeMap<String, String>,
+    /// The files being expanded, outermost first, to catch cycles.
+    including: Vec<String>,
+    included: Vec<String>,
+    out: Expanded,
+}
+
+impl Preprocessor<'_> {
+    /// Expands `name`, which `from` includes, if it's not the top-level file.
+    fn file(&mut self, name: &str, from: Option<&Origin>) -> Result<(), Error> {
+        let error = |message: String| match from {
+            Some(from) => Error { file: from.file.clone(), line: from.line, message },
+            None => Error { file: name.to_string(), line: 1, message },
+        };
+        if self.including.iter().any(|f| f == name) {
+            let mut cycle = self.including.clone();
+            cycle.push(name.to_string());
+            return Err(error(format!("#include cycle: {}", cycle.join(" -> "))));
+        }
+        if self.included.iter().any(|f| f == name) {
+            return Ok(());
+        }
+        let source = (self.load)(name).map_err(|e| error(format!("can't include \"{}\": {}", name, e)))?;
+        self.including.push(name.to_string());
+        self.included.push(name.to_string());
+
+        let mut conditionals: Vec<Conditional> = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            let origin = Origin { file: name.to_string(), line: i + 1 };
+            let active = conditionals.last().is_none_or(Conditional::active);
+            let Some(directive) = line.trim().strip_prefix('#') else {
+                if active {
+                    self.out.text.push_str(&self.substitute(line));
+                    self.out.text.push('\n');
+                    self.out.lines.push(origin);
+                }
+                continue;
+            };
+            let error = |message: String| Error { file: origin.file.clone(), line: origin.line, message };
+            let (keyword, rest) = directive.split_once(char::is_whitespace).unwrap_or((directive, ""));
+            let rest = rest.trim();
+            match keyword {
+                "ifdef" | "ifndef" => {
+                    let defined = self.defines.contains_key(identifier(keyword, rest).map_err(error)?);
+                    conditionals.push(Conditional { line: origin.line, condition: defined == (keyword == "ifdef"), outer: active, in_else: false });
+                }
+                "else" => match conditionals.last_mut() {
+                    Some(open) if !open.in_else => open.in_else = true,
+                    Some(_) => return Err(error("#else after #else".to_string())),
+                    None => return Err(error("#else without #ifdef".to_string())),
+                },
+                "endif" => {
+                    if conditionals.pop().is_none() {
+                        return Err(error("#endif without #ifdef".to_string()));
+                    }
+                }
+                _ if !active => {}
+                "include" => {
+                    let path = rest.strip_prefix('"').and_then(|r| r.strip_suffix('"'));
+                    let path = path.filter(|p| !p.is_empty()).ok_or_else(|| error("expected #include \"path\"".to_string()))?;
+                    self.file(path, Some(&origin))?;
+                }
+                "define" => {
+                    let (name, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    let name = identifier(keyword, name).map_err(error)?;
+                    let value = self.substitute(value.trim());
+                    self.defines.insert(name.to_string(), value);
+                }
+                "undef" => {
+                    let name = identifier(keyword, rest).map_err(error)?;
+                    self.defines.remove(name);
+                }
+                _ => return Err(error(format!("unknown directive #{}", keyword))),
+            }
+        }
+        if let Some(open) = conditionals.last() {
+            return Err(Error { file: name.to_string(), line: open.line, message: "#ifdef without #endif".to_string() });
+        }
+        self.including.pop();
+        Ok(())
+    }
+
+    /// `line` with each defined name replaced, up to any `//` comment.
+    fn substitute(&self, line: &str) -> String {
+        if self.defines.is_empty() {
+            return line.to_string();
+        }
+        let (code, comment) = match line.find("//") {
+            Some(i) => line.split_at(i),
+            None => (line, ""),
+        };
+        let mut out = String::with_capacity(line.len());
+        let mut word = String::new();
+        for c in code.chars().chain(std::iter::once('\0')) {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                word.push(c);
+                continue;
+            }
+            // A word starting with a digit is a number, like `1e5`, not a name.
+            match self.defines.get(&word) {
+                Some(value) if !word.starts_with(|c: char| c.is_ascii_digit()) => out.push_str(value),
+                _ => out.push_str(&word),
+            }
+            word.clear();
+            if c != '\0' {
+                out.push(c);
+            }
+        }
+        out.push_str(comment);
+        out
+    }
+}
+
+/// The name `#keyword` takes, checked.
+fn identifier<'a>(keyword: &str, name: &'a str) -> Result<&'a str, String> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match name {
+        "" => Err(format!("#{} needs a name", keyword)),
+        _ if !valid => Err(format!("'{}' is not a valid name for #{}", name, keyword)),
+        _ => Ok(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expands `file` from in-memory `files`, given as `(name, source)`.
+    fn run(file: &str, files: &[(&str, &str)]) -> Result<Expanded, Error> {
+        let mut load = |name: &str| {
+            files.iter().find(|(n, _)| *n == name).map(|(_, source)| source.to_string()).ok_or_else(|| "not found".to_string())
+        };
+        expand(file, &mut load)
+    }
+
+    fn origin(file: &str, line: usize) -> Origin {
+        Origin { file: file.to_string(), line }
+    }
+
+    #[test]
+    fn includes_each_file_once() {
+        let files = [
+            ("main.wgsl", "#include \"lib/a.wgsl\"\n#include \"lib/b.wgsl\"\nfn main() {}"),
+            ("lib/a.wgsl", "#include \"lib/b.wgsl\"\nfn a() {}"),
+            ("lib/b.wgsl", "fn b() {}"),
+        ];
+        assert_eq!(run("main.wgsl", &files).unwrap().text, "fn b() {}\nfn a() {}\nfn main() {}\n");
+    }
+
+    #[test]
+    fn reports_include_cycles_where_they_close() {
+        let files = [
+            ("main.wgsl", "#include \"lib/a.wgsl\""),
+            ("lib/a.wgsl", "// a\n#include \"lib/b.wgsl\""),
+            ("lib/b.wgsl", "#include \"lib/a.wgsl\""),
+        ];
+        let error = run("main.wgsl", &files).unwrap_err();
+        assert_eq!(error.to_string(), "lib/b.wgsl:1: #include cycle: main.wgsl -> lib/a.wgsl -> lib/b.wgsl -> lib/a.wgsl");
+        let error = run("main.wgsl", &[("main.wgsl", "\n#include \"main.wgsl\"")]).unwrap_err();
+        assert_eq!(error.to_string(), "main.wgsl:2: #include cycle: main.wgsl -> main.wgsl");
+        let error = run("main.wgsl", &[("main.wgsl", "#include \"lib/missing.wgsl\"")]).unwrap_err();
+        assert_eq!(error.to_string(), "main.wgsl:1: can't include \"lib/missing.wgsl\": not found");
+    }
+
+    #[test]
+    fn keeps_the_lines_of_the_branch_taken() {
+        let source = "#define FAST
+#ifdef FAST
+fast
+#ifndef FAST
+never
+#else
+nested
+#endif
+#else
+slow
+#endif
+#undef FAST
+#ifdef FAST
+gone
+#else
+after undef
+#endif";
+        assert_eq!(run("main.wgsl", &[("main.wgsl", source)]).unwrap().text, "fast\nnested\nafter undef\n");
+        // Nothing in a branch not taken runs, not even an #include.
+        let source = "#ifdef MISSING\n#include \"nowhere.wgsl\"\n#define X 1\n#endif\nX";
+        assert_eq!(run("main.wgsl", &[("main.wgsl", source)]).unwrap().text, "X\n");
+    }
+
+    #[test]
+    fn reports_unbalanced_conditionals() {
+        let cases = [
+            ("#else", "main.wgsl:1: #else without #ifdef"),
+            ("#endif", "main.wgsl:1: #endif without #ifdef"),
+            ("#ifdef A\n#else\n#else\n#endif", "main.wgsl:3: #else after #else"),
+            ("x\n#ifndef A\ny", "main.wgsl:2: #ifdef without #endif"),
+            ("#ifdef 1A\n#endif", "main.wgsl:1: '1A' is not a valid name for #ifdef"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(run("main.wgsl", &[("main.wgsl", source)]).unwrap_err().to_string(), expected, "{}", source);
+        }
+    }
+
+    #[test]
+    fn substitutes_defines_outside_comments() {
+        let source = "#define OCTAVES 4\nlet n = OCTAVES; // OCTAVES\nlet x = 1e5 + OCTAVES_2;";
+        assert_eq!(run("main.wgsl", &[("main.wgsl", source)]).unwrap().text, "let n = 4; // OCTAVES\nlet x = 1e5 + OCTAVES_2;\n");
+    }
+
+    #[test]
+    fn maps_output_lines_to_their_source() {
+        let files = [
+            ("main.wgsl", "// header\n#include \"lib/noise.wgsl\"\n#ifdef NOISE\nfn main() {}\n#endif"),
+            ("lib/noise.wgsl", "#define NOISE\n\nfn noise() {}"),
+        ];
+        let expanded = run("main.wgsl", &files).unwrap();
+        assert_eq!(expanded.text, "// header\n\nfn noise() {}\nfn main() {}\n");
+        assert_eq!(expanded.lines, [origin("main.wgsl", 1), origin("lib/noise.wgsl", 2), origin("lib/noise.wgsl", 3), origin("main.wgsl", 4)]);
+        assert_eq!(expanded.origin(3), Some(&origin("lib/noise.wgsl", 3)));
+        assert_eq!(expanded.origin(4), Some(&origin("main.wgsl", 4)));
+        assert_eq!(expanded.origin(0), None);
+        assert_eq!(expanded.origin(5), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Animated switches between shaders. While one runs, the outgoing and
+//! incoming shaders each draw into a texture and `src/renderer/composite.wgsl`
+//! mixes the two as the progress goes from 0 to 1.
+
+use std::fmt;
+
+/// How one shader gives way to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Switch at once.
+    Cut,
+    Crossfade,
+    /// Noise blotches of the new shader spread over the old one.
+    Dissolve,
+    /// A circle of the new shader grows from the middle.
+    Wipe,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 4] = [Kind::Cut, Kind::Crossfade, Kind::Dissolve, Kind::Wipe];
+
+    /// The names `--transition` takes, in the order of [`Kind::ALL`].
+    pub const NAMES: &'static [&'static str] = &["cut", "crossfade", "dissolve", "wipe"];
+
+    pub fn name(self) -> &'static str {
+        Kind::NAMES[self as usize]
+    }
+
+    pub fn parse(name: &str) -> Option<Kind> {
+        Kind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// `transition.mode` in composite.wgsl.
+    pub fn mode(self) -> u32 {
+        self as u32
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The longest `--duration`, in seconds.
+pub const MAX_DURATION: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub kind: Kind,
+    /// In seconds.
+    pub duration: f32,
+}
+
+impl Default for Transition {
+    /// What the shader dropdown and a plain `shader NAME` use.
+    fn default() -> Self {
+        Transition { kind: Kind::Crossfade, duration: 1.0 }
+    }
+}
+
+impl Transition {
+    /// Takes `--transition KIND` and `--duration SECONDS` out of `args`,
+    /// returning the transition and the arguments left over.
+    pub fn from_args<'a>(args: &[&'a str]) -> Result<(Transition, Vec<&'a str>), String> {
+        let mut transition = Transition::default();
+        let mut rest = Vec::new();
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            match arg {
+                "--transition" => {
+                    let name = args.next().ok_or("--transition needs a kind")?;
+                    transition.kind = Kind::parse(name)
+                        .ok_or_else(|| format!("unknown transition '{}' (try {})", name, Kind::NAMES.join(", ")))?;
+                }
+                "--duration" => {
+                    let seconds = args.next().ok_or("--duration needs a number of seconds")?;
+                    transition.duration = seconds
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|d| (0.0..=MAX_DURATION).contains(d))
+                        .ok_or_else(|| format!("--duration must be between 0 and {} seconds", MAX_DURATION))?;
+                }
+                _ => rest.push(arg),
+            }
+        }
+        Ok((transition, rest))
+    }
+
+    /// Whether there's anything to animate.
+    pub fn is_instant(&self) -> bool {
+        self.kind == Kind::Cut || self.duration <= 0.0
+    }
+
+    /// How far along it is `elapsed` seconds in, eased at both ends; 1 once
+    /// it's over.
+    pub fn progress(&self, elapsed: f32) -> f32 {
+        if self.is_instant() {
+            return 1.0;
+        }
+        let t = (elapsed / self.duration).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for kind in Kind::ALL {
+            assert_eq!(Kind::parse(kind.name()), Some(kind));
+            assert_eq!(kind.to_string(), kind.name());
+        }
+        assert_eq!(Kind::parse("fade"), None);
+    }
+
+    #[test]
+    fn takes_its_options_out_of_the_arguments() {
+        let (transition, rest) = Transition::from_args(&["fire", "--transition", "wipe", "--duration", "2.5"]).unwrap();
+        assert_eq!(transition, Transition { kind: Kind::Wipe, duration: 2.5 });
+        assert_eq!(rest, ["fire"]);
+        assert_eq!(Transition::from_args(&["fire"]), Ok((Transition::default(), vec!["fire"])));
+        let (transition, _) = Transition::from_args(&["--duration", "0"]).unwrap();
+        assert_eq!(transition.duration, 0.0);
+        assert!(transition.is_instant());
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        let cases = [
+            (&["--transition", "fade"][..], "unknown transition 'fade' (try cut, crossfade, dissolve, wipe)"),
+            (&["--transition"], "--transition needs a kind"),
+            (&["--duration"], "--duration needs a number of seconds"),
+            (&["--duration", "-1"], "--duration must be between 0 and 10 seconds"),
+            (&["--duration", "10.5"], "--duration must be between 0 and 10 seconds"),
+            (&["--duration", "NaN"], "--duration must be between 0 and 10 seconds"),
+            (&["--duration", "soon"], "--duration must be between 0 and 10 seconds"),
+        ];
+        for (args, message) in cases {
+            assert_eq!(Transition::from_args(args), Err(message.to_string()), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn progress_eases_from_zero_to_one() {
+        let fade = Transition { kind: Kind::Crossfade, duration: 2.0 };
+        assert_eq!(fade.progress(-1.0), 0.0);
+        assert_eq!(fade.progress(0.0), 0.0);
+        assert_eq!(fade.progress(1.0), 0.5);
+        assert!(fade.progress(0.5) < 0.25, "eased in");
+        assert_eq!(fade.progress(2.0), 1.0);
+        assert_eq!(fade.progress(60.0), 1.0);
+    }
+
+    #[test]
+    fn instant_transitions_finish_at_once() {
+        let cut = Transition { kind: Kind::Cut, duration: 5.0 };
+        assert!(cut.is_instant());
+        assert_eq!(cut.progress(0.0), 1.0);
+        let zero = Transition { kind: Kind::Wipe, duration: 0.0 };
+        assert_eq!(zero.progress(0.0), 1.0);
+        assert!(!Transition::default().is_instant());
+    }
+}
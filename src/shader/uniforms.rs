@@ -0,0 +1,124 @@
+//! The uniforms every shader reads, in one place.
+//!
+//! Shaders don't declare `Uniforms` themselves: build.rs and the renderer
+//! append [`wgsl`] to each one. `renderer::Uniforms` asserts at compile time
+//! that it puts every field at [`offset`], and build.rs checks the same
+//! offsets against naga's layout of the WGSL.
+
+use super::header::MAX_PARAMS;
+
+pub struct Field {
+    pub name: &'static str,
+    pub wgsl: &'static str,
+    /// Byte offset under WGSL's uniform layout rules.
+    pub offset: u32,
+}
+
+pub const FIELDS: &[Field] = &[
+    Field { name: "time", wgsl: "f32", offset: 0 },
+    Field { name: "mouse", wgsl: "vec2<f32>", offset: 8 },
+    Field { name: "resolution", wgsl: "vec2<f32>", offset: 16 },
+    // PARAM_VEC4S vec4s; see `Params::packed`.
+    Field { name: "params", wgsl: "array<vec4<f32>, 5>", offset: 32 },
+];
+
+/// The size of the whole struct, padding included.
+pub const SIZE: u32 = 112;
+
+/// How many vec4s [`MAX_PARAMS`] floats take in `params`.
+pub const PARAM_VEC4S: usize = MAX_PARAMS.div_ceil(4);
+
+// The WGSL above is spelled out, so changing MAX_PARAMS has to fail here
+// rather than leave the shaders reading a different struct.
+const _: () = {
+    assert!(array_len(field("params").wgsl.as_bytes()) == PARAM_VEC4S, "params must hold MAX_PARAMS floats");
+    assert!(offset("params") + 16 * PARAM_VEC4S == SIZE as usize, "SIZE must end after params");
+};
+
+/// The offset of the field called `name`; a missing one fails to compile.
+pub const fn offset(name: &str) -> usize {
+    field(name).offset as usize
+}
+
+const fn field(name: &str) -> &'static Field {
+    let mut i = 0;
+    while i < FIELDS.len() {
+        if same(name.as_bytes(), FIELDS[i].name.as_bytes()) {
+            return &FIELDS[i];
+        }
+        i += 1;
+    }
+    panic!("no uniform by that name");
+}
+
+/// `N` in `wgsl`, an `array<T, N>`.
+const fn array_len(wgsl: &[u8]) -> usize {
+    let mut i = wgsl.len() - 1;
+    while wgsl[i - 1].is_ascii_digit() {
+        i -= 1;
+    }
+    let mut n = 0;
+    while wgsl[i].is_ascii_digit() {
+        n = n * 10 + (wgsl[i] - b'0') as usize;
+        i += 1;
+    }
+    n
+}
+
+/// `a == b`, which isn't const yet.
+const fn same(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The `Uniforms` struct and its binding, to append to a shader.
+pub fn wgsl() -> String {
+    let mut wgsl = String::from("struct Uniforms {\n");
+    for field in FIELDS {
+        wgsl.push_str(&format!("    {}: {},\n", field.name, field.wgsl));
+    }
+    wgsl.push_str("}\n\n@group(0) @binding(0) var<uniform> uniforms: Uniforms;\n");
+    wgsl
+}
+
+#[cfg(test)]
+mod tests {
+    use naga::proc::Layouter;
+    use naga::TypeInner;
+
+    use super::*;
+
+    #[test]
+    fn matches_nagas_layout() {
+        let module = naga::front::wgsl::parse_str(&wgsl()).unwrap();
+        let mut layouter = Layouter::default();
+        layouter.update(module.to_ctx()).unwrap();
+        let (handle, ty) = module.types.iter().find(|(_, ty)| ty.name.as_deref() == Some("Uniforms")).unwrap();
+        let TypeInner::Struct { members, .. } = &ty.inner else {
+            panic!("Uniforms isn't a struct");
+        };
+
+        assert_eq!(members.len(), FIELDS.len());
+        let mut end = 0;
+        for (field, member) in FIELDS.iter().zip(members) {
+            assert_eq!(member.name.as_deref(), Some(field.name));
+            // Each field starts at the first offset its alignment allows.
+            let layout = layouter[member.ty];
+            let offset = layout.alignment.round_up(end);
+            assert_eq!(field.offset, offset, "{}", field.name);
+            assert_eq!(member.offset, offset, "{}", field.name);
+            end = offset + layout.size;
+        }
+        assert_eq!(layouter[handle].size, SIZE);
+        assert_eq!(layouter[handle].alignment.round_up(end), SIZE);
+    }
+}
@@ -0,0 +1,260 @@
+//! Checks shaders with naga: at build time, so a mistake fails the build
+//! instead of the background, and again when one is loaded at runtime.
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use naga::{AddressSpace, Binding, Handle, Module, ShaderStage, Span, Type, TypeInner};
+
+use super::graph::Pass;
+use super::uniforms;
+
+/// A problem at a 1-based line of the source that was checked.
+pub struct Error {
+    pub line: usize,
+    pub message: String,
+    /// naga's rendering of the error with the source around it, when it has one.
+    pub report: Option<String>,
+}
+
+impl Error {
+    fn at(source: &str, span: Span, message: String) -> Self {
+        let line = if span.is_defined() { span.location(source).line_number as usize } else { 1 };
+        Error { line, message, report: None }
+    }
+}
+
+/// `error` and the errors that caused it, innermost last.
+fn describe(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Parses and validates `source`, labelled `path` in naga's report.
+pub fn module(path: &str, source: &str) -> Result<Module, Error> {
+    let module = naga::front::wgsl::parse_str(source).map_err(|e| Error {
+        line: e.location(source).map_or(1, |l| l.line_number as usize),
+        message: e.message().to_string(),
+        report: Some(e.emit_to_string_with_path(source, path)),
+    })?;
+    Validator::new(ValidationFlags::all(), Capabilities::default()).validate(&module).map_err(|e| Error {
+        line: e.location(source).map_or(1, |l| l.line_number as usize),
+        message: describe(&e),
+        report: Some(e.emit_to_string_with_path(source, path)),
+    })?;
+    Ok(module)
+}
+
+/// The line `fn <name>` is declared on, since naga keeps no span for entry points.
+fn entry_line(source: &str, name: &str) -> usize {
+    let declaration = format!("fn{}(", name);
+    source.lines().position(|line| line.replace(' ', "").contains(&declaration)).map_or(1, |i| i + 1)
+}
+
+/// The bindings of an entry point's arguments or result, looking inside structs.
+fn bindings(module: &Module, binding: Option<&Binding>, ty: Handle<Type>) -> Vec<(Binding, Handle<Type>)> {
+    match (binding, &module.types[ty].inner) {
+        (Some(binding), _) => vec![(binding.clone(), ty)],
+        (None, TypeInner::Struct { members, .. }) => {
+            members.iter().filter_map(|m| m.binding.clone().map(|binding| (binding, m.ty))).collect()
+        }
+        (None, _) => Vec::new(),
+    }
+}
+
+/// The entry point `name` of `stage`, which is what the renderer calls.
+fn entry<'a>(module: &'a Module, stage: ShaderStage, name: &str) -> Option<&'a naga::EntryPoint> {
+    module.entry_points.iter().find(|ep| ep.name == name && ep.stage == stage)
+}
+
+/// Checks that the fragment shader's entry point `name` reads only what the
+/// vertex shader's `main` writes, and writes the one color target.
+pub fn interface(vertex: &Module, fragment: &Module, source: &str, name: &str) -> Vec<Error> {
+    let line = entry_line(source, name);
+    let error = |message: String| Error { line, message, report: None };
+    let Some(vs) = entry(vertex, ShaderStage::Vertex, "main") else {
+        return vec![Error { line: 1, message: "vs.wgsl has no @vertex fn main".to_string(), report: None }];
+    };
+    let Some(fs) = entry(fragment, ShaderStage::Fragment, name) else {
+        return vec![error(format!("expected a @fragment fn {}, which the renderer draws with", name))];
+    };
+
+    let outputs = vs.function.result.as_ref().map(|r| bindings(vertex, r.binding.as_ref(), r.ty)).unwrap_or_default();
+    let mut errors = Vec::new();
+    for arg in &fs.function.arguments {
+        for (binding, ty) in bindings(fragment, arg.binding.as_ref(), arg.ty) {
+            let Binding::Location { location, .. } = binding else {
+                continue;
+            };
+            let output = outputs.iter().find(|(b, _)| matches!(b, Binding::Location { location: l, .. } if *l == location));
+            match output {
+                None => errors.push(error(format!("@location({}) is read, but vs.wgsl doesn't write it", location))),
+                Some((_, vs_ty)) if vertex.types[*vs_ty].inner != fragment.types[ty].inner => errors.push(error(format!(
+                    "@location({}) is a different type here than in vs.wgsl",
+                    location
+                ))),
+                Some(_) => {}
+            }
+        }
+    }
+    let targets = fs.function.result.as_ref().map(|r| bindings(fragment, r.binding.as_ref(), r.ty)).unwrap_or_default();
+    if !targets.iter().any(|(b, _)| matches!(b, Binding::Location { location: 0, .. })) {
+        errors.push(error(format!("fn {} must return a color at @location(0)", name)));
+    }
+    for (binding, _) in &targets {
+        if let Binding::Location { location: location @ 1.., .. } = binding {
+            errors.push(error(format!("@location({}) is written, but there's only one color target", location)));
+        }
+    }
+    errors
+}
+
+/// Checks that the only resources are the uniform buffer at `@group(0)
+/// @binding(0)` and the passes' inputs generated in `@group(1)`.
+pub fn resources(module: &Module, source: &str) -> Vec<Error> {
+    let mut errors = Vec::new();
+    for (handle, global) in module.global_variables.iter() {
+        let Some(binding) = &global.binding else {
+            continue;
+        };
+        let span = module.global_variables.get_span(handle);
+        if binding.group == 1 && global.name.as_deref().is_some_and(|name| name.starts_with("input_")) {
+            continue;
+        }
+        if (binding.group, binding.binding) != (0, 0) {
+            errors.push(Error::at(source, span, "only @group(0) @binding(0), the uniforms, is bound".to_string()));
+        } else if global.space != AddressSpace::Uniform {
+            errors.push(Error::at(source, span, "@group(0) @binding(0) must be var<uniform>".to_string()));
+        }
+    }
+    errors
+}
+
+/// Checks that each pass reads only the inputs its `@pass` line lists,
+/// since the renderer binds nothing else for it.
+pub fn inputs(module: &Module, passes: &[Pass], source: &str) -> Vec<Error> {
+    let Ok(info) = Validator::new(ValidationFlags::all(), Capabilities::default()).validate(module) else {
+        return Vec::new();
+    };
+    let mut errors = Vec::new();
+    for pass in passes {
+        let Some(index) = module.entry_points.iter().position(|ep| ep.name == pass.name && ep.stage == ShaderStage::Fragment) else {
+            continue;
+        };
+        let uses = info.get_entry_point(index);
+        for (handle, global) in module.global_variables.iter() {
+            let Some(input) = global.name.as_deref().and_then(|n| n.strip_prefix("input_")).and_then(|n| n.strip_suffix("_texture")) else {
+                continue;
+            };
+            if !uses[handle].is_empty() && !pass.inputs.iter().any(|i| i.wgsl_name() == input) {
+                errors.push(Error {
+                    line: entry_line(source, &pass.name),
+                    message: format!("pass {} reads input_{}, but its @pass line doesn't list it", pass.name, input),
+                    report: None,
+                });
+            }
+        }
+    }
+    errors
+}
+
+/// Checks that naga lays out the generated `Uniforms` as `uniforms::FIELDS` says.
+pub fn layout(module: &Module) -> Vec<String> {
+    let Some(TypeInner::Struct { members, span: size }) =
+        module.types.iter().find(|(_, ty)| ty.name.as_deref() == Some("Uniforms")).map(|(_, ty)| &ty.inner)
+    else {
+        return vec!["the uniforms must be a struct called Uniforms".to_string()];
+    };
+    let mut errors = Vec::new();
+    for field in uniforms::FIELDS {
+        match members.iter().find(|m| m.name.as_deref() == Some(field.name)) {
+            None => errors.push(format!("the uniforms have no '{}'", field.name)),
+            Some(m) if m.offset != field.offset => {
+                errors.push(format!("naga puts '{}' at byte {}, but the table says {}", field.name, m.offset, field.offset))
+            }
+            Some(_) => {}
+        }
+    }
+    if *size != uniforms::SIZE {
+        errors.push(format!("naga makes the uniforms {} bytes, but the table says {}", size, uniforms::SIZE));
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAGMENT: &str = "struct Uniforms { time: f32 }
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+@fragment
+fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uniforms.time, 0.0, 0.0, 1.0);
+}
+";
+
+    /// `module`, or a panic with its error.
+    fn valid(source: &str) -> Module {
+        module("test.wgsl", source).unwrap_or_else(|e| panic!("line {}: {}", e.line, e.message))
+    }
+
+    fn lines(errors: Vec<Error>) -> Vec<(usize, String)> {
+        errors.into_iter().map(|e| (e.line, e.message)).collect()
+    }
+
+    #[test]
+    fn passes_a_valid_shader() {
+        let vertex = valid(super::super::VERTEX_SHADER);
+        let fragment = valid(FRAGMENT);
+        assert!(interface(&vertex, &fragment, FRAGMENT, "main").is_empty());
+        assert!(resources(&fragment, FRAGMENT).is_empty());
+    }
+
+    #[test]
+    fn reports_parse_errors_at_their_line() {
+        let source = FRAGMENT.replace("uniforms.time, 0.0", "uniforms.time 0.0");
+        let Err(error) = module("broken.wgsl", &source) else {
+            panic!("a missing comma parsed");
+        };
+        assert_eq!(error.line, 6);
+        assert!(error.report.as_deref().is_some_and(|report| report.contains("broken.wgsl:6:")), "{:?}", error.report);
+    }
+
+    #[test]
+    fn reports_validation_errors_at_their_line() {
+        let source = FRAGMENT.replace("    return vec4", "    let x: f32 = 1u;\n    return vec4");
+        let Err(error) = module("broken.wgsl", &source) else {
+            panic!("a u32 went into an f32");
+        };
+        assert_eq!(error.line, 6);
+        assert!(error.report.as_deref().is_some_and(|report| report.contains("broken.wgsl")), "{:?}", error.report);
+    }
+
+    #[test]
+    fn checks_the_interface_with_the_vertex_shader() {
+        let vertex = valid(super::super::VERTEX_SHADER);
+        let source = FRAGMENT.replace("@builtin(position) position: vec4<f32>", "@location(2) uv: vec2<f32>").replace("-> @location(0)", "-> @location(1)");
+        let fragment = valid(&source);
+        assert_eq!(
+            lines(interface(&vertex, &fragment, &source, "main")),
+            [
+                (5, "@location(2) is read, but vs.wgsl doesn't write it".to_string()),
+                (5, "fn main must return a color at @location(0)".to_string()),
+                (5, "@location(1) is written, but there's only one color target".to_string()),
+            ]
+        );
+        assert_eq!(lines(interface(&vertex, &fragment, &source, "blur")), [(1, "expected a @fragment fn blur, which the renderer draws with".to_string())]);
+    }
+
+    #[test]
+    fn allows_only_the_uniforms_to_be_bound() {
+        let source = format!("{}@group(0) @binding(1) var<uniform> extra: vec4<f32>;\n", FRAGMENT);
+        let fragment = valid(&source);
+        assert_eq!(lines(resources(&fragment, &source)), [(8, "only @group(0) @binding(0), the uniforms, is bound".to_string())]);
+    }
+}
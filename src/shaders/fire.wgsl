@@ -0,0 +1,30 @@
+// @name Smoke
+// @description Warped noise cut into black and white smoke
+// @author Connor Brown
+// @tags noise, monochrome
+// @hidden
+// @param speed 0.1 0.0..1.0 How fast the smoke drifts
+// @param threshold 0.5 0.0..1.0 Where the pattern turns from black to white
+// @param softness 0.1 0.01..0.5 How blurry that edge is
+
+// `uniforms` and the `param_` functions are appended by build.rs; see
+// src/shader/uniforms.rs.
+
+#include "lib/noise.wgsl"
+
+@fragment
+fn main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    var uv = frag_coord.xy / uniforms.resolution.xy;
+    uv.x = uv.x * (uniforms.resolution.x / uniforms.resolution.y);
+
+    let speed = param_speed();
+    
+    var q = vec2<f32>(fbm(uv + uniforms.time * speed), fbm(uv + vec2<f32>(5.2, 1.3) + uniforms.time * speed));
+    var r = vec2<f32>(fbm(uv + q * 0.5 + vec2<f32>(1.7, 9.2) + uniforms.time * speed * 0.5), fbm(uv + q * 0.5 + vec2<f32>(8.3, 2.8) + uniforms.time * speed * 0.5));
+
+    let f = fbm(uv + r);
+
+    let color = smoothstep(param_threshold() - param_softness(), param_threshold() + param_softness(), f);
+
+    return vec4<f32>(color, color, color, 1.0);
+}
\ No newline at end of file
@@ -1,57 +1,51 @@
-struct Uniforms {
-    time: f32,
-    _padding1: vec3<f32>,
-    mouse: vec2<f32>,
-    _padding2: vec2<f32>,
-    resolution: vec2<f32>,
-    _padding3: vec2<f32>,
-    _extra_padding1: vec4<f32>,
-    _extra_padding2: vec4<f32>,
-    _extra_padding3: vec4<f32>,
-    _extra_padding4: vec4<f32>,
-    _extra_padding5: vec4<f32>,
+// @name Gel
+// @description Slow, layered noise in soft monochrome
+// @author Connor Brown
+// @tags noise, monochrome
+// @default
+// @param speed 1.0 0.0..4.0 How fast the pattern drifts
+// @param scale 1.0 0.25..4.0 Zoom; larger shows more, smaller blobs
+// @param brightness 1.0 0.0..2.0 Overall brightness
+
+// `uniforms` and the `param_` functions are appended by build.rs; see
+// src/shader/uniforms.rs.
+
+#include "lib/noise.wgsl"
+
+// Squared, so the pattern is mostly dark with soft highlights.
+fn soft_noise(p: vec2<f32>) -> f32 {
+    let n = noise(p);
+    return n * n;
 }
 
-@group(0) @binding(0) var<uniform> uniforms: Uniforms;
-
-fn rand(n: vec2<f32>) -> f32 { 
-    return fract(sin(dot(n, vec2<f32>(12.9898, 4.1414))) * 43758.5453);
-}
-
-fn noise(p: vec2<f32>) -> f32 {
-    let ip = floor(p);
-    var u = fract(p);
-    u = u*u*(3.0-2.0*u);
-
-    let res = mix(
-        mix(rand(ip),rand(ip+vec2<f32>(1.0,0.0)),u.x),
-        mix(rand(ip+vec2<f32>(0.0,1.0)),rand(ip+vec2<f32>(1.0,1.0)),u.x),u.y);
-    return res*res;
-}
-
-fn fbm(p_in: vec2<f32>) -> f32 {
+// A rotated fbm that drifts with time, for the domain warping in pattern().
+fn flow(p_in: vec2<f32>) -> f32 {
     let mtx = mat2x2<f32>( 0.80,  0.60, -0.60,  0.80 );
     var p = p_in;
     var f = 0.0;
 
-    f = f + 0.500000*noise( p + uniforms.time  ); p = mtx*p*2.02;
-    f = f + 0.031250*noise( p ); p = mtx*p*2.01;
-    f = f + 0.250000*noise( p ); p = mtx*p*2.03;
-    f = f + 0.125000*noise( p ); p = mtx*p*2.01;
-    f = f + 0.062500*noise( p ); p = mtx*p*2.04;
-    f = f + 0.015625*noise( p + sin(uniforms.time) );
+    f = f + 0.500000*soft_noise( p + time() ); p = mtx*p*2.02;
+    f = f + 0.031250*soft_noise( p ); p = mtx*p*2.01;
+    f = f + 0.250000*soft_noise( p ); p = mtx*p*2.03;
+    f = f + 0.125000*soft_noise( p ); p = mtx*p*2.01;
+    f = f + 0.062500*soft_noise( p ); p = mtx*p*2.04;
+    f = f + 0.015625*soft_noise( p + sin(time()) );
 
     return f/0.96875;
 }
 
+fn time() -> f32 {
+    return uniforms.time * param_speed();
+}
+
 fn pattern(p: vec2<f32>) -> f32 {
-    return fbm( p + fbm( p + fbm( p ) ) );
+    return flow( p + flow( p + flow( p ) ) );
 }
 
 @fragment
 fn main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
     var uv = frag_coord.xy / uniforms.resolution.xy;
     uv.x = uv.x * (uniforms.resolution.x / uniforms.resolution.y);
-    let shade = pattern(uv);
+    let shade = pattern(uv * param_scale()) * param_brightness();
     return vec4<f32>(shade, shade, shade, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+// Value noise and fractal Brownian motion, shared by the shaders.
+//
+// Define NOISE_OCTAVES before including this for a cheaper or more
+// detailed fbm.
+
+#ifndef NOISE_OCTAVES
+#define NOISE_OCTAVES 6
+#endif
+
+fn hash(p: vec2<f32>) -> f32 {
+    let h = dot(p, vec2<f32>(127.1, 311.7));
+    return fract(sin(h) * 43758.5453123);
+}
+
+// Smoothly interpolated hash values, in 0..1.
+fn noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+
+    let a = hash(i + vec2<f32>(0.0, 0.0));
+    let b = hash(i + vec2<f32>(1.0, 0.0));
+    let c = hash(i + vec2<f32>(0.0, 1.0));
+    let d = hash(i + vec2<f32>(1.0, 1.0));
+
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+// NOISE_OCTAVES layers of noise, each at twice the frequency and half the
+// amplitude of the last.
+fn fbm(p_in: vec2<f32>) -> f32 {
+    var p = p_in;
+    var value = 0.0;
+    var amplitude = 0.5;
+
+    for (var i = 0; i < NOISE_OCTAVES; i = i + 1) {
+        value = value + amplitude * noise(p);
+        p = p * 2.0;
+        amplitude = amplitude * 0.5;
+    }
+    return value;
+}
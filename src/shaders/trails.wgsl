@@ -0,0 +1,50 @@
+// @name Trails
+// @description Drifting points of ink that leave fading trails; the mouse draws too
+// @author Connor Brown
+// @tags feedback, monochrome
+// @param fade 0.97 0.8..0.995 How much of each frame's trail is left the next
+// @param speed 1.0 0.0..4.0 How fast the points and the flow move
+// @param brightness 0.8 0.0..2.0 Overall brightness
+// @pass trail 0.5 trail.previous, noise
+// @pass main 1.0 trail
+
+// `uniforms`, the `param_` functions and the passes' `input_` and
+// `resolution_` functions are appended by build.rs; see
+// src/shader/uniforms.rs and src/shader/graph.rs.
+
+// A soft dot at `center`, round whatever the screen's aspect ratio.
+fn spot(uv: vec2<f32>, center: vec2<f32>) -> f32 {
+    let aspect = uniforms.resolution.x / uniforms.resolution.y;
+    let d = (uv - center) * vec2<f32>(aspect, 1.0);
+    return smoothstep(0.02, 0.0, length(d));
+}
+
+// Last frame's trail, carried along a noise flow and faded, plus new ink.
+@fragment
+fn trail(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = frag_coord.xy / resolution_trail();
+    let t = uniforms.time * param_speed();
+
+    let flow = input_noise(uv * 0.5 + vec2<f32>(t * 0.01, 0.0)).xy - 0.5;
+    // Less one step of the 8-bit texture, or faint trails would never fade out.
+    let previous = max(input_trail_previous(uv - flow * 0.004).r * param_fade() - 1.0 / 255.0, 0.0);
+
+    var ink = spot(uv, uniforms.mouse / uniforms.resolution);
+    for (var i = 0; i < 3; i = i + 1) {
+        let fi = f32(i);
+        let point = vec2<f32>(
+            0.5 + 0.35 * sin(t * (0.3 + 0.1 * fi) + fi * 2.1),
+            0.5 + 0.35 * cos(t * (0.23 + 0.07 * fi) + fi),
+        );
+        ink = ink + spot(uv, point);
+    }
+    return vec4<f32>(vec3<f32>(min(previous + ink, 1.0)), 1.0);
+}
+
+// The half-resolution trail, scaled up to the screen.
+@fragment
+fn main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = frag_coord.xy / resolution_main();
+    let shade = input_trail(uv).r * param_brightness();
+    return vec4<f32>(shade, shade, shade, 1.0);
+}
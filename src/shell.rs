@@ -0,0 +1,355 @@
+//! The terminal's little shell: parsing a line and running it as pipelines.
+//!
+//! Commands are stream filters. Each gets its `argv` and, inside a pipeline, the
+//! previous command's stdout, and returns an [`Output`]. What the commands
+//! actually are is up to the [`Host`], so this runs (and can be tested) natively.
+
+use crate::command::{CommandResult, Effect};
+
+pub mod complete;
+pub mod filters;
+pub mod parse;
+
+pub use parse::{parse, Condition, ParseError, Part, Pipeline, Redirect, Script, SimpleCommand, Word};
+
+/// What one command produced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Output {
+    /// Passed to the next command in a pipeline, otherwise printed.
+    pub stdout: String,
+    /// Always printed, even mid-pipeline.
+    pub stderr: String,
+    /// Zero for success; `&&` only continues on zero.
+    pub status: i32,
+    pub effects: Vec<Effect>,
+}
+
+impl Output {
+    pub fn text(stdout: impl Into<String>) -> Self {
+        Output { stdout: stdout.into(), ..Output::default() }
+    }
+
+    /// A failed command with `message` on stderr.
+    pub fn error(message: impl Into<String>) -> Self {
+        Output { stderr: message.into(), status: 1, ..Output::default() }
+    }
+
+    pub fn effect(effect: Effect) -> Self {
+        Output { effects: vec![effect], ..Output::default() }
+    }
+
+    pub fn with_effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+}
+
+impl From<String> for Output {
+    fn from(stdout: String) -> Self {
+        Output::text(stdout)
+    }
+}
+
+impl From<&str> for Output {
+    fn from(stdout: &str) -> Self {
+        Output::text(stdout)
+    }
+}
+
+/// The screen a command's stdout lands on when it isn't piped or redirected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Terminal {
+    pub columns: usize,
+    /// Whether escape sequences for colors and links may be used.
+    pub color: bool,
+}
+
+impl Default for Terminal {
+    fn default() -> Self {
+        Terminal { columns: 80, color: true }
+    }
+}
+
+/// Supplies the commands and the files that `>` writes to.
+#[allow(async_fn_in_trait)]
+pub trait Host {
+    /// Runs one command. `stdin` is `None` for the first command of a pipeline,
+    /// and `terminal` is `None` unless its stdout goes straight to the screen.
+    async fn run(&mut self, argv: &[String], stdin: Option<&str>, terminal: Option<Terminal>) -> Output;
+
+    /// Stores `contents` at `path` for a `>` or `>>` redirect.
+    fn write_file(&mut self, path: &str, contents: &str, append: bool) -> Result<(), String>;
+
+    /// What `name` stands for when it starts a command.
+    fn alias(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Whether a line starting with `name` is passed to it unparsed; see
+    /// [`Command::raw`](crate::command::Command::raw).
+    fn raw(&self, _name: &str) -> bool {
+        false
+    }
+
+    /// The value of `$name`. `$?` is handled by the shell.
+    fn var(&self, _name: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Appends `text` to the terminal output as whole lines.
+fn print(out: &mut String, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(text);
+}
+
+/// Parses and runs `line`, collecting what should reach `terminal`.
+pub async fn run(line: &str, host: &mut impl Host, terminal: Terminal) -> CommandResult {
+    let line = line.trim();
+    let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    if host.raw(name) {
+        let argv: Vec<String> = std::iter::once(name).chain(Some(rest.trim_start()).filter(|r| !r.is_empty())).map(str::to_string).collect();
+        let out = host.run(&argv, None, Some(terminal)).await;
+        let mut result = CommandResult { effects: out.effects, ..CommandResult::default() };
+        print(&mut result.output, &out.stderr);
+        print(&mut result.output, &out.stdout);
+        return result;
+    }
+
+    let script = match parse(line, &|name| host.alias(name)) {
+        Ok(script) => script,
+        Err(e) => return CommandResult::text(e.to_string()),
+    };
+
+    let mut result = CommandResult::default();
+    let mut status = 0;
+    for (condition, pipeline) in &script.items {
+        if *condition == Condition::Success && status != 0 {
+            continue;
+        }
+        status = run_pipeline(pipeline, host, terminal, status, &mut result).await;
+    }
+    if result.output.ends_with('\n') {
+        result.output.pop();
+    }
+    result
+}
+
+/// `word` with its variables filled in; `$?` is `status`, the last pipeline's.
+fn expand(word: &Word, host: &impl Host, status: i32) -> String {
+    word.expand(|name| if name == "?" { Some(status.to_string()) } else { host.var(name) })
+}
+
+async fn run_pipeline(
+    pipeline: &Pipeline,
+    host: &mut impl Host,
+    terminal: Terminal,
+    last_status: i32,
+    result: &mut CommandResult,
+) -> i32 {
+    let mut stdin: Option<String> = None;
+    let mut status = 0;
+    let last = pipeline.commands.len() - 1;
+    for (i, command) in pipeline.commands.iter().enumerate() {
+        let to_screen = i == last && command.redirect.is_none();
+        let argv: Vec<String> = command.argv.iter().map(|word| expand(word, host, last_status)).collect();
+        let mut out = host.run(&argv, stdin.as_deref(), Some(terminal).filter(|_| to_screen)).await;
+        print(&mut result.output, &out.stderr);
+        for effect in out.effects.drain(..) {
+            // Anything printed before a clear would be wiped by it anyway, and
+            // the page clears before printing, so one leading clear does.
+            if effect == Effect::Clear {
+                result.output.clear();
+                if result.effects.first() != Some(&Effect::Clear) {
+                    result.effects.insert(0, Effect::Clear);
+                }
+                continue;
+            }
+            result.effects.push(effect);
+        }
+        status = out.status;
+
+        stdin = match &command.redirect {
+            Some(redirect) => {
+                let path = expand(&redirect.path, host, last_status);
+                if let Err(e) = host.write_file(&path, &out.stdout, redirect.append) {
+                    print(&mut result.output, &format!("{}: {}", path, e));
+                    status = 1;
+                }
+                Some(String::new())
+            }
+            None if i == last => {
+                print(&mut result.output, &out.stdout);
+                None
+            }
+            None => Some(out.stdout),
+        };
+    }
+    status
+}
+
+/// Runs `future`, which must not wait on anything: what the native tests'
+/// commands all do.
+#[cfg(test)]
+pub(crate) fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    use std::task::{Context, Poll, Waker};
+    let mut future = std::pin::pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the future waited"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// A few commands and an in-memory file store.
+    #[derive(Default)]
+    struct FakeHost {
+        files: BTreeMap<String, String>,
+        /// Every `argv` run, in order.
+        ran: Vec<Vec<String>>,
+    }
+
+    impl Host for FakeHost {
+        async fn run(&mut self, argv: &[String], stdin: Option<&str>, terminal: Option<Terminal>) -> Output {
+            self.ran.push(argv.to_vec());
+            match argv.iter().map(String::as_str).collect::<Vec<&str>>().as_slice() {
+                ["echo", words @ ..] => words.join(" ").into(),
+                ["cat"] => stdin.unwrap_or_default().into(),
+                ["cat", path] => match self.files.get(*path) {
+                    Some(contents) => contents.as_str().into(),
+                    None => Output::error(format!("cat: {}: No such file", path)),
+                },
+                ["upper"] => stdin.unwrap_or_default().to_uppercase().into(),
+                ["tty"] => (if terminal.is_some() { "screen" } else { "pipe" }).into(),
+                ["false"] => Output { status: 1, ..Output::default() },
+                ["warn", message] => Output { stdout: "out".to_string(), stderr: message.to_string(), status: 2, effects: Vec::new() },
+                ["clear"] => Output::effect(Effect::Clear),
+                ["py", code] => Output::effect(Effect::RunPython { code: code.to_string() }),
+                ["open", url] => Output::effect(Effect::OpenUrl { url: url.to_string() }),
+                [name, ..] => Output { status: 127, ..Output::error(format!("{}: command not found", name)) },
+                [] => Output::default(),
+            }
+        }
+
+        fn write_file(&mut self, path: &str, contents: &str, append: bool) -> Result<(), String> {
+            if path.ends_with('/') {
+                return Err("Is a directory".to_string());
+            }
+            let file = self.files.entry(path.to_string()).or_default();
+            if !append {
+                file.clear();
+            }
+            file.push_str(contents);
+            Ok(())
+        }
+
+        fn raw(&self, name: &str) -> bool {
+            name == "py"
+        }
+
+        fn var(&self, name: &str) -> Option<String> {
+            (name == "USER").then(|| "guest".to_string())
+        }
+    }
+
+    fn run_line(line: &str, host: &mut FakeHost) -> CommandResult {
+        block_on(run(line, host, Terminal::default()))
+    }
+
+    #[test]
+    fn clear_wipes_only_what_came_before_it() {
+        let mut host = FakeHost::default();
+        let result = run_line("echo before; clear; echo hi", &mut host);
+        assert_eq!(result, CommandResult { output: "hi".to_string(), effects: vec![Effect::Clear] });
+        let result = run_line("open https://example.com; echo a; clear && echo b; clear; echo c", &mut host);
+        assert_eq!(result.output, "c");
+        assert_eq!(result.effects, [Effect::Clear, Effect::OpenUrl { url: "https://example.com".to_string() }]);
+    }
+
+    #[test]
+    fn raw_commands_get_the_line_as_typed() {
+        let mut host = FakeHost::default();
+        let result = run_line("  py a = \"x | y\"; print(a) > out && $USER  ", &mut host);
+        assert_eq!(result.effects, [Effect::RunPython { code: "a = \"x | y\"; print(a) > out && $USER".to_string() }]);
+        assert_eq!(host.ran, [["py", "a = \"x | y\"; print(a) > out && $USER"]]);
+        assert!(host.files.is_empty());
+        run_line("py", &mut host);
+        assert_eq!(host.ran[1], ["py"]);
+    }
+
+    #[test]
+    fn pipes_stdout_to_the_next_command() {
+        let mut host = FakeHost::default();
+        assert_eq!(run_line("echo hello | upper", &mut host).output, "HELLO");
+        assert_eq!(run_line("echo a | cat | upper | cat", &mut host).output, "A");
+        // Only the last command of a pipeline draws on the screen.
+        assert_eq!(run_line("tty | cat", &mut host).output, "pipe");
+        assert_eq!(run_line("tty", &mut host).output, "screen");
+        assert_eq!(run_line("tty > t; cat t", &mut host).output, "pipe");
+    }
+
+    #[test]
+    fn and_skips_after_a_failure_but_semicolon_does_not() {
+        let cases = [
+            ("false && echo no", ""),
+            ("false; echo yes", "yes"),
+            ("echo a && echo b", "a\nb"),
+            ("false && echo no; echo yes", "yes"),
+            ("echo a; false && echo no && echo nor; echo b", "a\nb"),
+            // A pipeline's status is its last command's.
+            ("false | echo piped && echo then", "piped\nthen"),
+            ("echo x | false && echo no", ""),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(run_line(line, &mut FakeHost::default()).output, expected, "{}", line);
+        }
+        let mut host = FakeHost::default();
+        run_line("false && echo no && echo nor", &mut host);
+        assert_eq!(host.ran, [["false"]]);
+    }
+
+    #[test]
+    fn redirects_write_and_append_files() {
+        let mut host = FakeHost::default();
+        assert_eq!(run_line("echo one > f; echo two >> f", &mut host).output, "");
+        assert_eq!(host.files["f"], "onetwo");
+        run_line("echo three > f", &mut host);
+        assert_eq!(host.files["f"], "three");
+        // A redirected command's successor reads nothing.
+        assert_eq!(run_line("echo a > g | upper", &mut host).output, "");
+        assert_eq!(host.files["g"], "a");
+        assert_eq!(run_line("echo $USER > $USER.txt; cat guest.txt", &mut host).output, "guest");
+
+        let result = run_line("echo a > dir/ && echo no", &mut host);
+        assert_eq!(result.output, "dir/: Is a directory");
+    }
+
+    #[test]
+    fn question_mark_is_the_last_status() {
+        let mut host = FakeHost::default();
+        assert_eq!(run_line("echo $?", &mut host).output, "0");
+        assert_eq!(run_line("false; echo $?", &mut host).output, "1");
+        assert_eq!(run_line("nope; echo $?; echo $?", &mut host).output, "nope: command not found\n127\n0");
+        assert_eq!(run_line("warn oops; echo $?", &mut host).output, "oops\nout\n2");
+    }
+
+    #[test]
+    fn stderr_is_printed_even_mid_pipeline() {
+        let mut host = FakeHost::default();
+        assert_eq!(run_line("warn oops | upper", &mut host).output, "oops\nOUT");
+        assert_eq!(run_line("cat missing | upper", &mut host).output, "cat: missing: No such file");
+        assert_eq!(run_line("warn oops > f", &mut host).output, "oops");
+        assert_eq!(host.files["f"], "out");
+        assert_eq!(run_line("echo 'unterminated", &mut host).output, parse("echo 'unterminated", &|_| None).unwrap_err().to_string());
+    }
+}
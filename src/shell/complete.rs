@@ -0,0 +1,340 @@
+//! Tab completion, driven by the registry's argument specs.
+
+use serde::Serialize;
+
+use crate::command::{ArgKind, Registry, Value, NO_COLOR};
+use crate::resume::Sections;
+
+/// The values completion can't know on its own.
+pub trait Sources {
+    fn shaders(&self) -> Vec<String>;
+
+    /// The active shader's parameter names.
+    fn params(&self) -> Vec<String>;
+
+    /// Paths starting with `partial`. Directories end in `/`, so completion stops there.
+    fn paths(&self, partial: &str) -> Vec<String>;
+}
+
+/// The result of completing the word under the cursor.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Completion {
+    /// Character offset where the word being completed starts; the text from
+    /// here to the cursor is replaced with `prefix`.
+    pub start: usize,
+    /// Every match, unescaped, for listing.
+    pub candidates: Vec<String>,
+    /// The longest text every candidate starts with, escaped for the shell.
+    /// A single complete match also gets a trailing space.
+    pub prefix: String,
+}
+
+/// Completes the word ending at `cursor` (a character offset into `line`).
+pub fn complete(line: &str, cursor: usize, registry: &Registry, sources: &dyn Sources) -> Completion {
+    let chars: Vec<char> = line.chars().take(cursor).collect();
+    let scan = scan(&chars);
+
+    let mut candidates = if scan.redirect {
+        sources.paths(&scan.partial)
+    } else if scan.words.is_empty() {
+        let names = registry.commands().flat_map(|c| std::iter::once(c.name()).chain(c.aliases().iter().copied()));
+        starting_with(&scan.partial, names.map(str::to_string))
+    } else {
+        arguments(&scan, registry, sources)
+    };
+    candidates.sort();
+    candidates.dedup();
+
+    let mut prefix = common_prefix(&candidates).unwrap_or_else(|| scan.partial.clone());
+    if scan.quote.is_none() {
+        prefix = escape(&prefix);
+    }
+    if let [only] = candidates.as_slice() {
+        if !only.ends_with('/') {
+            prefix.extend(scan.quote);
+            prefix.push(' ');
+        }
+    }
+    Completion { start: scan.start, candidates, prefix }
+}
+
+fn arguments(scan: &Scan, registry: &Registry, sources: &dyn Sources) -> Vec<String> {
+    let Some(command) = registry.get(&scan.words[0]) else {
+        return Vec::new();
+    };
+    let mut spec = command.args();
+    let mut given = &scan.words[1..];
+    // A subcommand brings its own arguments.
+    if let Some(ArgKind::Subcommand(args)) = given.first().and_then(|w| spec.iter().find(|arg| arg.name == w)).map(|arg| arg.kind) {
+        spec = args;
+        given = &given[1..];
+    }
+    let option = |name: &str| spec.iter().find(|arg| arg.name == name);
+
+    // The word before the cursor may be an option waiting for its value.
+    if let Some(ArgKind::Option(value)) = given.last().and_then(|w| option(w)).map(|arg| arg.kind) {
+        return values(value, &scan.partial, registry, sources);
+    }
+    if scan.partial.starts_with('-') {
+        let flags = spec.iter().filter(|arg| matches!(arg.kind, ArgKind::Flag | ArgKind::Option(_)));
+        let flags = flags.map(|arg| arg.name).chain([NO_COLOR]);
+        return starting_with(&scan.partial, flags.map(str::to_string));
+    }
+
+    // Count the positionals already given, skipping flags and option values.
+    let mut index = 0;
+    let mut words = given.iter();
+    while let Some(word) = words.next() {
+        match option(word).map(|arg| arg.kind) {
+            Some(ArgKind::Option(_)) => {
+                words.next();
+            }
+            Some(ArgKind::Flag) => {}
+            _ => index += 1,
+        }
+    }
+    let positionals: Vec<Value> = spec
+        .iter()
+        .filter_map(|arg| match arg.kind {
+            ArgKind::Positional(value) => Some(value),
+            _ => None,
+        })
+        .collect();
+    let mut candidates = match positionals.get(index) {
+        Some(&value) => values(value, &scan.partial, registry, sources),
+        None => Vec::new(),
+    };
+    if index == 0 {
+        let subcommands = spec.iter().filter(|arg| matches!(arg.kind, ArgKind::Subcommand(_)));
+        candidates.extend(starting_with(&scan.partial, subcommands.map(|arg| arg.name.to_string())));
+    }
+    candidates
+}
+
+fn values(value: Value, partial: &str, registry: &Registry, sources: &dyn Sources) -> Vec<String> {
+    match value {
+        Value::Text => Vec::new(),
+        Value::OneOf(words) => starting_with(partial, words.iter().map(|w| w.to_string())),
+        Value::Shader => starting_with(partial, sources.shaders()),
+        Value::Param => starting_with(partial, sources.params()),
+        Value::Section => starting_with(partial, Sections::NAMES.iter().map(|s| s.to_string())),
+        Value::Path => sources.paths(partial),
+        Value::Command => starting_with(partial, registry.commands().map(|c| c.name().to_string())),
+    }
+}
+
+fn starting_with(partial: &str, candidates: impl IntoIterator<Item = String>) -> Vec<String> {
+    candidates.into_iter().filter(|c| c.starts_with(partial)).collect()
+}
+
+fn common_prefix(candidates: &[String]) -> Option<String> {
+    let (first, rest) = candidates.split_first()?;
+    let mut len = first.len();
+    for candidate in rest {
+        let shared = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8());
+        len = len.min(shared);
+    }
+    Some(first[..len].to_string())
+}
+
+/// Backslash-escapes anything the parser would otherwise treat specially.
+fn escape(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    for c in word.chars() {
+        if c.is_whitespace() || "'\"\\|;&>$".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The current command's words up to the cursor, as far as completion cares.
+struct Scan {
+    /// Finished words of the command under the cursor, name first.
+    words: Vec<String>,
+    /// The unquoted text of the word being completed.
+    partial: String,
+    start: usize,
+    /// The quote the partial word opened and hasn't closed; completions go inside it.
+    quote: Option<char>,
+    /// The partial word is the target of `>`.
+    redirect: bool,
+}
+
+/// Ends the word being scanned. A redirect target isn't an argument, so it's dropped.
+fn finish(word: &mut Option<(usize, String)>, words: &mut Vec<String>, redirect: &mut bool) {
+    if let Some((_, text)) = word.take() {
+        if *redirect {
+            *redirect = false;
+        } else {
+            words.push(text);
+        }
+    }
+}
+
+/// A forgiving version of the parser's tokenizer: unterminated quotes are fine,
+/// and only the command after the last `|`, `;` or `&&` is kept.
+fn scan(chars: &[char]) -> Scan {
+    let mut words = Vec::new();
+    let mut word: Option<(usize, String)> = None;
+    let mut quote: Option<char> = None;
+    let mut redirect = false;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some('"') if c == '\\' && matches!(chars.get(i + 1), Some('"' | '\\' | '$')) => {
+                i += 1;
+                word.get_or_insert((i, String::new())).1.push(chars[i]);
+            }
+            Some(_) => word.get_or_insert((i, String::new())).1.push(c),
+            None => match c {
+                '\'' | '"' => {
+                    quote = Some(c);
+                    word.get_or_insert((i, String::new()));
+                }
+                '\\' => {
+                    let start = i;
+                    i += 1;
+                    if let Some(&escaped) = chars.get(i) {
+                        word.get_or_insert((start, String::new())).1.push(escaped);
+                    }
+                }
+                c if c.is_whitespace() => finish(&mut word, &mut words, &mut redirect),
+                '|' | ';' | '&' => {
+                    finish(&mut word, &mut words, &mut redirect);
+                    words.clear();
+                    redirect = false;
+                }
+                '>' => {
+                    finish(&mut word, &mut words, &mut redirect);
+                    redirect = true;
+                }
+                c => word.get_or_insert((i, String::new())).1.push(c),
+            },
+        }
+        i += 1;
+    }
+
+    match word {
+        // Replace after the opening quote, so the user's quote stays put.
+        Some((start, partial)) if quote.is_some() && Some(chars[start]) == quote => {
+            Scan { words, partial, start: start + 1, quote, redirect }
+        }
+        Some((start, partial)) => Scan { words, partial, start, quote: None, redirect },
+        None => Scan { words, partial: String::new(), start: chars.len(), quote: None, redirect },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{Arg, Command, Context, RunFuture};
+    use crate::shell::Output;
+
+    struct Shader;
+
+    impl Command for Shader {
+        fn name(&self) -> &'static str {
+            "shader"
+        }
+
+        fn usage(&self) -> &'static str {
+            "shader NAME | shader set PARAM VALUE | shader edit [NAME] | shader reset"
+        }
+
+        fn description(&self) -> &'static str {
+            "A command with subcommands"
+        }
+
+        fn args(&self) -> &'static [Arg] {
+            &[
+                Arg { name: "NAME", kind: ArgKind::Positional(Value::Shader), help: "" },
+                Arg {
+                    name: "set",
+                    kind: ArgKind::Subcommand(&[
+                        Arg { name: "PARAM", kind: ArgKind::Positional(Value::Param), help: "" },
+                        Arg { name: "VALUE", kind: ArgKind::Positional(Value::Text), help: "" },
+                    ]),
+                    help: "",
+                },
+                Arg { name: "edit", kind: ArgKind::Subcommand(&[Arg { name: "NAME", kind: ArgKind::Positional(Value::Shader), help: "" }]), help: "" },
+                Arg { name: "reset", kind: ArgKind::Subcommand(&[]), help: "" },
+                Arg { name: "--transition", kind: ArgKind::Option(Value::OneOf(&["cut", "crossfade"])), help: "" },
+            ]
+        }
+
+        fn run<'a>(&'a self, _ctx: Context<'a>, _args: &'a [String]) -> RunFuture<'a> {
+            Box::pin(async { Output::default() })
+        }
+    }
+
+    struct Fixed;
+
+    impl Sources for Fixed {
+        fn shaders(&self) -> Vec<String> {
+            vec!["gel".to_string(), "trails".to_string(), "waves".to_string()]
+        }
+
+        fn params(&self) -> Vec<String> {
+            vec!["speed".to_string(), "scale".to_string()]
+        }
+
+        fn paths(&self, partial: &str) -> Vec<String> {
+            starting_with(partial, ["resume.txt".to_string(), "shaders/".to_string()])
+        }
+    }
+
+    fn candidates(line: &str) -> Vec<String> {
+        let mut registry = Registry::new();
+        registry.register(Shader);
+        complete(line, line.chars().count(), &registry, &Fixed).candidates
+    }
+
+    #[test]
+    fn completes_subcommands_with_the_first_positional() {
+        assert_eq!(candidates("shader "), ["edit", "gel", "reset", "set", "trails", "waves"]);
+        assert_eq!(candidates("shader r"), ["reset"]);
+        assert_eq!(candidates("shader --transition cut "), ["edit", "gel", "reset", "set", "trails", "waves"]);
+    }
+
+    #[test]
+    fn completes_a_subcommands_own_arguments() {
+        assert_eq!(candidates("shader set "), ["scale", "speed"]);
+        assert_eq!(candidates("shader set speed "), Vec::<String>::new());
+        assert_eq!(candidates("shader edit t"), ["trails"]);
+        assert_eq!(candidates("shader reset "), Vec::<String>::new());
+        assert_eq!(candidates("shader gel "), Vec::<String>::new());
+    }
+
+    #[test]
+    fn completes_options_and_their_values() {
+        assert_eq!(candidates("shader --"), ["--no-color", "--transition"]);
+        assert_eq!(candidates("shader --transition c"), ["crossfade", "cut"]);
+    }
+
+    #[test]
+    fn completes_commands_and_redirects() {
+        assert_eq!(candidates("sh"), ["shader"]);
+        assert_eq!(candidates("help sh"), ["shader"]);
+        assert_eq!(candidates("echo hi > r"), ["resume.txt"]);
+    }
+
+    #[test]
+    fn escapes_and_quotes_the_prefix() {
+        let mut registry = Registry::new();
+        registry.register(Shader);
+        let completion = complete("shader w", 8, &registry, &Fixed);
+        assert_eq!((completion.start, completion.prefix.as_str()), (7, "waves "));
+        let completion = complete("shader 'w", 9, &registry, &Fixed);
+        assert_eq!((completion.start, completion.prefix.as_str()), (8, "waves' "));
+    }
+}
@@ -0,0 +1,374 @@
+//! Commands that only transform their input, so they need nothing from the page.
+
+use regex::RegexBuilder;
+
+use super::Output;
+use crate::ansi;
+use crate::command::{usage_error, Arg, ArgKind, Command, Context, Effect, Registry, RunFuture, Value};
+
+/// Adds `echo`, `grep`, `wc`, `sort`, `uniq` and `less` to `registry`.
+pub fn register(registry: &mut Registry) {
+    registry.register(Echo);
+    registry.register(Grep);
+    registry.register(Wc);
+    registry.register(Sort);
+    registry.register(Uniq);
+    registry.register(Less);
+}
+
+/// Borrows `args` as `&str`s for slice patterns.
+pub(crate) fn strs(args: &[String]) -> Vec<&str> {
+    args.iter().map(String::as_str).collect()
+}
+
+/// Splits leading single-letter flags (`-i -n`, or combined as `-in`) from the
+/// rest of `args`, or `None` for a flag not in `allowed`. `--` ends the flags early.
+pub(crate) fn short_flags<'a>(args: &'a [String], allowed: &str) -> Option<(Vec<char>, &'a [String])> {
+    let mut flags = Vec::new();
+    let mut rest = args;
+    while let Some((arg, tail)) = rest.split_first() {
+        if arg == "--" {
+            return Some((flags, tail));
+        }
+        let Some(letters) = arg.strip_prefix('-').filter(|l| !l.is_empty()) else {
+            break;
+        };
+        for letter in letters.chars() {
+            if !allowed.contains(letter) {
+                return None;
+            }
+            flags.push(letter);
+        }
+        rest = tail;
+    }
+    Some((flags, rest))
+}
+
+struct Echo;
+
+impl Command for Echo {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+
+    fn usage(&self) -> &'static str {
+        "echo [TEXT...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print its arguments"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "TEXT", kind: ArgKind::Positional(Value::Text), help: "words to print, separated by spaces" }]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        // A leading `--` only shields what follows from `--no-color`.
+        let args = match args {
+            [first, rest @ ..] if first == "--" => rest,
+            _ => args,
+        };
+        Box::pin(async move { args.join(" ").into() })
+    }
+}
+
+struct Grep;
+
+impl Command for Grep {
+    fn name(&self) -> &'static str {
+        "grep"
+    }
+
+    fn usage(&self) -> &'static str {
+        "grep [-i] [-n] [-v] PATTERN"
+    }
+
+    fn description(&self) -> &'static str {
+        "Keep the input lines matching a regular expression"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "-i", kind: ArgKind::Flag, help: "ignore case" },
+            Arg { name: "-n", kind: ArgKind::Flag, help: "prefix each line with its line number" },
+            Arg { name: "-v", kind: ArgKind::Flag, help: "keep the lines that don't match" },
+            Arg { name: "PATTERN", kind: ArgKind::Positional(Value::Text), help: "regular expression, e.g. 'C\\+\\+|Rust'" },
+        ]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let Some((flags, [pattern])) = short_flags(args, "inv") else {
+                return usage_error(self);
+            };
+            let regex = match RegexBuilder::new(pattern).case_insensitive(flags.contains(&'i')).build() {
+                Ok(regex) => regex,
+                Err(e) => return Output { status: 2, ..Output::error(format!("grep: {}", e)) },
+            };
+            let invert = flags.contains(&'v');
+            let color = ctx.color();
+            let matched: Vec<String> = ctx
+                .stdin
+                .unwrap_or_default()
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| regex.is_match(line) != invert)
+                .map(|(i, line)| {
+                    let line = if color && !invert {
+                        // An empty match (`grep 'x*'`) has nothing to paint.
+                        regex
+                            .replace_all(line, |m: &regex::Captures| if m[0].is_empty() { String::new() } else { ansi::paint(&[ansi::BOLD, ansi::RED], &m[0]) })
+                            .into_owned()
+                    } else {
+                        line.to_string()
+                    };
+                    match (flags.contains(&'n'), color) {
+                        (true, true) => format!("{}:{}", ansi::paint(&[ansi::GREEN], &(i + 1).to_string()), line),
+                        (true, false) => format!("{}:{}", i + 1, line),
+                        (false, _) => line,
+                    }
+                })
+                .collect();
+            // Like grep, "nothing matched" is a failure so `&&` can test for it.
+            Output { stdout: matched.join("\n"), status: if matched.is_empty() { 1 } else { 0 }, ..Output::default() }
+        })
+    }
+}
+
+struct Wc;
+
+impl Command for Wc {
+    fn name(&self) -> &'static str {
+        "wc"
+    }
+
+    fn usage(&self) -> &'static str {
+        "wc [-l] [-w] [-c]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Count the lines, words and bytes of the input"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "-l", kind: ArgKind::Flag, help: "count lines" },
+            Arg { name: "-w", kind: ArgKind::Flag, help: "count words" },
+            Arg { name: "-c", kind: ArgKind::Flag, help: "count bytes" },
+        ]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let Some((flags, [])) = short_flags(args, "lwc") else {
+                return usage_error(self);
+            };
+            let input = ctx.stdin.unwrap_or_default();
+            let counts = [('l', input.lines().count()), ('w', input.split_whitespace().count()), ('c', input.len())];
+            // Like wc, the counts asked for come in this order, and no flags means all three.
+            counts
+                .iter()
+                .filter(|(flag, _)| flags.is_empty() || flags.contains(flag))
+                .map(|(_, count)| count.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+                .into()
+        })
+    }
+}
+
+struct Sort;
+
+impl Command for Sort {
+    fn name(&self) -> &'static str {
+        "sort"
+    }
+
+    fn usage(&self) -> &'static str {
+        "sort [-r] [-n] [-u]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Sort the input lines"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "-r", kind: ArgKind::Flag, help: "reverse the order" },
+            Arg { name: "-n", kind: ArgKind::Flag, help: "compare leading numbers numerically" },
+            Arg { name: "-u", kind: ArgKind::Flag, help: "drop repeated lines" },
+        ]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let Some((flags, [])) = short_flags(args, "rnu") else {
+                return usage_error(self);
+            };
+            let mut lines: Vec<&str> = ctx.stdin.unwrap_or_default().lines().collect();
+            if flags.contains(&'n') {
+                // Lines without a number sort first, as with `sort -n`.
+                let number = |line: &str| -> f64 {
+                    let line = line.trim_start();
+                    let end = line.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-')).unwrap_or(line.len());
+                    line[..end].parse().unwrap_or(f64::NEG_INFINITY)
+                };
+                lines.sort_by(|a, b| number(a).total_cmp(&number(b)).then(a.cmp(b)));
+            } else {
+                lines.sort();
+            }
+            if flags.contains(&'u') {
+                lines.dedup();
+            }
+            if flags.contains(&'r') {
+                lines.reverse();
+            }
+            lines.join("\n").into()
+        })
+    }
+}
+
+struct Uniq;
+
+impl Command for Uniq {
+    fn name(&self) -> &'static str {
+        "uniq"
+    }
+
+    fn usage(&self) -> &'static str {
+        "uniq [-c]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Collapse runs of identical input lines"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "-c", kind: ArgKind::Flag, help: "prefix each line with how often it repeated" }]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let Some((flags, [])) = short_flags(args, "c") else {
+                return usage_error(self);
+            };
+            let mut runs: Vec<(usize, &str)> = Vec::new();
+            for line in ctx.stdin.unwrap_or_default().lines() {
+                match runs.last_mut() {
+                    Some((count, last)) if *last == line => *count += 1,
+                    _ => runs.push((1, line)),
+                }
+            }
+            let lines: Vec<String> = runs
+                .into_iter()
+                .map(|(count, line)| if flags.contains(&'c') { format!("{:>7} {}", count, line) } else { line.to_string() })
+                .collect();
+            lines.join("\n").into()
+        })
+    }
+}
+
+struct Less;
+
+impl Command for Less {
+    fn name(&self) -> &'static str {
+        "less"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["more"]
+    }
+
+    fn usage(&self) -> &'static str {
+        "less"
+    }
+
+    fn description(&self) -> &'static str {
+        "Page through the input (space: next page, enter: next line, q: quit)"
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            if !args.is_empty() {
+                return usage_error(self);
+            }
+            // Only the page knows how tall the terminal is, so paging happens there.
+            Output::effect(Effect::More { text: ctx.stdin.unwrap_or_default().to_string() })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::{block_on, Terminal};
+
+    /// Runs `line` (split on spaces) with `stdin` piped in, on a color terminal.
+    fn run(line: &str, stdin: &str) -> Output {
+        let mut registry = Registry::new();
+        register(&mut registry);
+        let argv: Vec<String> = line.split(' ').map(str::to_string).collect();
+        block_on(registry.run(&argv, Some(stdin), Some(Terminal::default())))
+    }
+
+    #[test]
+    fn grep_colors_matches_unless_no_color() {
+        let red = |text: &str| ansi::paint(&[ansi::BOLD, ansi::RED], text);
+        assert_eq!(run("grep ru", "rust\ngo").stdout, format!("{}st", red("ru")));
+        assert_eq!(run("grep --no-color ru", "rust\ngo").stdout, "rust");
+        assert_eq!(run("grep -n o", "rust\ngo").stdout, format!("{}:g{}", ansi::paint(&[ansi::GREEN], "2"), red("o")));
+        assert_eq!(run("grep -n --no-color o", "rust\ngo").stdout, "2:go");
+    }
+
+    #[test]
+    fn grep_leaves_empty_matches_unpainted() {
+        assert_eq!(run("grep x*", "ab").stdout, "ab");
+        assert_eq!(run("grep o*", "go").stdout, format!("g{}", ansi::paint(&[ansi::BOLD, ansi::RED], "o")));
+    }
+
+    #[test]
+    fn wc_counts_what_its_flags_ask_for() {
+        let input = "one two\nthree\n";
+        assert_eq!(run("wc", input).stdout, "2 3 14");
+        assert_eq!(run("wc -l", input).stdout, "2");
+        assert_eq!(run("wc -w -l", input).stdout, "2 3");
+        assert_eq!(run("wc -cw", input).stdout, "3 14");
+        assert_eq!(run("wc -x", input).stderr, "usage: wc [-l] [-w] [-c]");
+    }
+
+    #[test]
+    fn sort_orders_by_flags() {
+        let input = "10 ten\nb\n9 nine\n-1 minus\na\n9 nine\n1.5 x";
+        let cases = [
+            ("sort", "-1 minus\n1.5 x\n10 ten\n9 nine\n9 nine\na\nb"),
+            // Lines without a number sort first, by text.
+            ("sort -n", "a\nb\n-1 minus\n1.5 x\n9 nine\n9 nine\n10 ten"),
+            ("sort -r", "b\na\n9 nine\n9 nine\n10 ten\n1.5 x\n-1 minus"),
+            ("sort -r -u", "b\na\n9 nine\n10 ten\n1.5 x\n-1 minus"),
+            ("sort -nru", "10 ten\n9 nine\n1.5 x\n-1 minus\nb\na"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(run(line, input).stdout, expected, "{}", line);
+        }
+        assert_eq!(run("sort -x", input).stderr, "usage: sort [-r] [-n] [-u]");
+    }
+
+    #[test]
+    fn uniq_collapses_only_adjacent_runs() {
+        let input = "a\na\nb\na\na\na";
+        assert_eq!(run("uniq", input).stdout, "a\nb\na");
+        assert_eq!(run("uniq -c", input).stdout, "      2 a\n      1 b\n      3 a");
+        assert_eq!(run("uniq -c", "").stdout, "");
+        assert_eq!(run("uniq -n", input).stderr, "usage: uniq [-c]");
+    }
+
+    #[test]
+    fn less_hands_its_input_to_the_page() {
+        assert_eq!(run("less", "a\nb").effects, [Effect::More { text: "a\nb".into() }]);
+        assert_eq!(run("more", "a").effects, [Effect::More { text: "a".into() }]);
+        let output = run("less resume", "a");
+        assert_eq!((output.status, output.stderr.as_str()), (1, "usage: less"));
+        assert!(output.effects.is_empty());
+    }
+}
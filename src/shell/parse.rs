@@ -0,0 +1,454 @@
+use std::fmt;
+
+/// A parsed command line: pipelines joined by `;` or `&&`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Script {
+    pub items: Vec<(Condition, Pipeline)>,
+}
+
+/// When a pipeline runs, relative to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// First in the script, or after `;`.
+    Always,
+    /// After `&&`: only if the previous pipeline succeeded.
+    Success,
+}
+
+/// Commands joined by `|`, each reading the previous one's stdout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub commands: Vec<SimpleCommand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleCommand {
+    pub argv: Vec<Word>,
+    pub redirect: Option<Redirect>,
+}
+
+/// `> path` (truncate) or `>> path` (append).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    pub path: Word,
+    pub append: bool,
+}
+
+/// A word with its quotes resolved but its variables left in, since they
+/// may be set by an earlier command on the same line.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Word {
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    Text(String),
+    /// `$NAME`, `${NAME}` or `$?`, outside single quotes.
+    Var(String),
+}
+
+impl Word {
+    /// Substitutes each variable with `var(name)`, or nothing if that's `None`.
+    /// Unlike sh, a value is never split into several words.
+    pub fn expand(&self, var: impl Fn(&str) -> Option<String>) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Var(name) => out.push_str(&var(name).unwrap_or_default()),
+            }
+        }
+        out
+    }
+
+    fn push(&mut self, c: char) {
+        match self.parts.last_mut() {
+            Some(Part::Text(text)) => text.push(c),
+            _ => self.parts.push(Part::Text(c.to_string())),
+        }
+    }
+}
+
+/// Shows the word with its variables as `${NAME}`.
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => f.write_str(text)?,
+                Part::Var(name) => write!(f, "${{{}}}", name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Character offset into the line where the problem was found.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "syntax error at column {}: {}", self.column + 1, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A word, and whether it was typed without quotes, escapes or
+    /// variables, which is what makes it eligible for alias expansion.
+    Word(Word, bool),
+    Pipe,
+    Semi,
+    AndAnd,
+    Gt,
+    GtGt,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(w, _) => format!("'{}'", w),
+            Token::Pipe => "'|'".to_string(),
+            Token::Semi => "';'".to_string(),
+            Token::AndAnd => "'&&'".to_string(),
+            Token::Gt => "'>'".to_string(),
+            Token::GtGt => "'>>'".to_string(),
+        }
+    }
+}
+
+fn error(column: usize, message: impl Into<String>) -> ParseError {
+    ParseError { column, message: message.into() }
+}
+
+/// Reads the variable reference at `chars[i]`, a `$`, returning its name and
+/// the index after it, or `None` if the `$` is just a dollar sign.
+fn variable(chars: &[char], i: usize) -> Result<Option<(String, usize)>, ParseError> {
+    let is_name = |c: &char| c.is_ascii_alphanumeric() || *c == '_';
+    match chars.get(i + 1) {
+        Some('?') => Ok(Some(("?".to_string(), i + 2))),
+        Some('{') => {
+            let Some(close) = chars[i + 2..].iter().position(|c| *c == '}') else {
+                return Err(error(i, "unterminated '${'"));
+            };
+            let name: String = chars[i + 2..i + 2 + close].iter().collect();
+            if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) || !name.chars().all(|c| is_name(&c)) {
+                return Err(error(i, format!("bad substitution '${{{}}}'", name)));
+            }
+            Ok(Some((name, i + 3 + close)))
+        }
+        Some(c) if c.is_ascii_alphabetic() || *c == '_' => {
+            let name: String = chars[i + 1..].iter().take_while(|c| is_name(c)).collect();
+            let end = i + 1 + name.len();
+            Ok(Some((name, end)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Splits `line` into words and operators, resolving quotes and escapes.
+///
+/// Single quotes are literal; inside double quotes a backslash only escapes
+/// `"`, `\` and `$`; elsewhere it escapes any character. `$NAME` is a
+/// variable anywhere but inside single quotes.
+fn tokenize(line: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '|' => {
+                tokens.push((start, Token::Pipe));
+                i += 1;
+            }
+            ';' => {
+                tokens.push((start, Token::Semi));
+                i += 1;
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                tokens.push((start, Token::AndAnd));
+                i += 2;
+            }
+            '&' => return Err(error(start, "background jobs ('&') are not supported")),
+            '>' if chars.get(i + 1) == Some(&'>') => {
+                tokens.push((start, Token::GtGt));
+                i += 2;
+            }
+            '>' => {
+                tokens.push((start, Token::Gt));
+                i += 1;
+            }
+            _ => {
+                let mut word = Word::default();
+                let mut plain = true;
+                while i < chars.len() {
+                    if chars[i] == '$' {
+                        if let Some((name, end)) = variable(&chars, i)? {
+                            word.parts.push(Part::Var(name));
+                            plain = false;
+                            i = end;
+                            continue;
+                        }
+                    }
+                    match chars[i] {
+                        c if c.is_whitespace() || "|;&>".contains(c) => break,
+                        '\'' => {
+                            plain = false;
+                            let open = i;
+                            i += 1;
+                            while i < chars.len() && chars[i] != '\'' {
+                                word.push(chars[i]);
+                                i += 1;
+                            }
+                            if i == chars.len() {
+                                return Err(error(open, "unterminated single quote"));
+                            }
+                            i += 1;
+                        }
+                        '"' => {
+                            plain = false;
+                            let open = i;
+                            i += 1;
+                            while i < chars.len() && chars[i] != '"' {
+                                if chars[i] == '$' {
+                                    if let Some((name, end)) = variable(&chars, i)? {
+                                        word.parts.push(Part::Var(name));
+                                        i = end;
+                                        continue;
+                                    }
+                                }
+                                if chars[i] == '\\' && matches!(chars.get(i + 1), Some('"' | '\\' | '$')) {
+                                    i += 1;
+                                }
+                                word.push(chars[i]);
+                                i += 1;
+                            }
+                            if i == chars.len() {
+                                return Err(error(open, "unterminated double quote"));
+                            }
+                            i += 1;
+                        }
+                        '\\' => {
+                            plain = false;
+                            let Some(&escaped) = chars.get(i + 1) else {
+                                return Err(error(i, "trailing backslash"));
+                            };
+                            word.push(escaped);
+                            i += 2;
+                        }
+                        c => {
+                            word.push(c);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push((start, Token::Word(word, plain)));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Replaces a plain word that starts a command with the tokens of its alias,
+/// if `aliases` has one. Like sh, an alias isn't expanded inside itself.
+fn expand_aliases(
+    tokens: Vec<(usize, Token)>,
+    aliases: &dyn Fn(&str) -> Option<String>,
+    expanding: &mut Vec<String>,
+) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut out: Vec<(usize, Token)> = Vec::with_capacity(tokens.len());
+    for (column, token) in tokens {
+        let starts_command = out.last().is_none_or(|(_, t)| matches!(t, Token::Pipe | Token::Semi | Token::AndAnd));
+        if let (true, Token::Word(word, true)) = (starts_command, &token) {
+            let name = word.to_string();
+            if let Some(value) = aliases(&name).filter(|_| !expanding.contains(&name)) {
+                let alias = tokenize(&value).map_err(|e| error(column, format!("in alias '{}': {}", name, e.message)))?;
+                expanding.push(name);
+                let alias = expand_aliases(alias, aliases, expanding)?;
+                expanding.pop();
+                // Errors inside the alias point at where it was used.
+                out.extend(alias.into_iter().map(|(_, token)| (column, token)));
+                continue;
+            }
+        }
+        out.push((column, token));
+    }
+    Ok(out)
+}
+
+/// Parses one terminal line, expanding any `aliases`. An empty (or
+/// all-whitespace) line gives an empty script.
+pub fn parse(line: &str, aliases: &dyn Fn(&str) -> Option<String>) -> Result<Script, ParseError> {
+    let tokens = expand_aliases(tokenize(line)?, aliases, &mut Vec::new())?;
+    let end = line.chars().count();
+    let mut tokens = tokens.into_iter().peekable();
+    let mut script = Script::default();
+    let mut condition = Condition::Always;
+
+    while tokens.peek().is_some() {
+        let mut commands = Vec::new();
+        loop {
+            let mut argv = Vec::new();
+            let mut redirect = None;
+            while let Some((column, token)) = tokens.next_if(|(_, t)| !matches!(t, Token::Pipe | Token::Semi | Token::AndAnd)) {
+                match token {
+                    Token::Word(word, _) if redirect.is_none() => argv.push(word),
+                    Token::Word(word, _) => return Err(error(column, format!("unexpected '{}' after redirect", word))),
+                    Token::Gt | Token::GtGt => {
+                        let append = token == Token::GtGt;
+                        match tokens.next() {
+                            Some((_, Token::Word(path, _))) if redirect.is_none() => redirect = Some(Redirect { path, append }),
+                            Some((column, _)) if redirect.is_some() => return Err(error(column, "only one redirect per command")),
+                            Some((column, other)) => return Err(error(column, format!("expected a file name, found {}", other.describe()))),
+                            None => return Err(error(end, "expected a file name after redirect")),
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            let next = tokens.peek().map(|(column, _)| *column).unwrap_or(end);
+            if argv.is_empty() {
+                return Err(error(next, "expected a command"));
+            }
+            commands.push(SimpleCommand { argv, redirect });
+            if tokens.next_if(|(_, t)| *t == Token::Pipe).is_none() {
+                break;
+            }
+        }
+        script.items.push((condition, Pipeline { commands }));
+
+        match tokens.next() {
+            None => break,
+            Some((_, Token::Semi)) => condition = Condition::Always,
+            Some((column, Token::AndAnd)) => {
+                if tokens.peek().is_none() {
+                    return Err(error(column, "expected a command after '&&'"));
+                }
+                condition = Condition::Success;
+            }
+            Some(_) => unreachable!(),
+        }
+    }
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_aliases(_: &str) -> Option<String> {
+        None
+    }
+
+    /// The script as `[word]`s, with its operators between them.
+    fn show(script: &Script) -> String {
+        let mut out = String::new();
+        for (i, (condition, pipeline)) in script.items.iter().enumerate() {
+            if i > 0 {
+                out.push_str(if *condition == Condition::Success { " && " } else { "; " });
+            }
+            let commands: Vec<String> = pipeline
+                .commands
+                .iter()
+                .map(|command| {
+                    let mut words: Vec<String> = command.argv.iter().map(|word| format!("[{}]", word)).collect();
+                    if let Some(redirect) = &command.redirect {
+                        words.push(format!("{} [{}]", if redirect.append { ">>" } else { ">" }, redirect.path));
+                    }
+                    words.join(" ")
+                })
+                .collect();
+            out.push_str(&commands.join(" | "));
+        }
+        out
+    }
+
+    #[test]
+    fn parses() {
+        let cases = [
+            ("", ""),
+            ("   ", ""),
+            ("echo hello  world", "[echo] [hello] [world]"),
+            ("echo 'a  b' \"c d\"", "[echo] [a  b] [c d]"),
+            ("echo 'it''s'", "[echo] [its]"),
+            ("echo it\\'s", "[echo] [it's]"),
+            ("echo \"say \\\"hi\\\" \\$x \\n\"", "[echo] [say \"hi\" $x \\n]"),
+            ("echo 'a|b;c&&d>e'", "[echo] [a|b;c&&d>e]"),
+            ("echo $HOME \"$HOME/x\" '$HOME' ${HOME}s $? $", "[echo] [${HOME}] [${HOME}/x] [$HOME] [${HOME}s] [${?}] [$]"),
+            ("ls | grep a | wc -l", "[ls] | [grep] [a] | [wc] [-l]"),
+            ("ls|wc", "[ls] | [wc]"),
+            ("a; b && c", "[a]; [b] && [c]"),
+            ("a && b; c", "[a] && [b]; [c]"),
+            ("a;", "[a]"),
+            ("echo a > f", "[echo] [a] > [f]"),
+            ("echo a>>f", "[echo] [a] >> [f]"),
+            ("echo a > 'my file'", "[echo] [a] > [my file]"),
+            ("cat f | sort > out && cat out", "[cat] [f] | [sort] > [out] && [cat] [out]"),
+        ];
+        for (line, expected) in cases {
+            match parse(line, &no_aliases) {
+                Ok(script) => assert_eq!(show(&script), expected, "parsing {:?}", line),
+                Err(e) => panic!("parsing {:?}: {}", line, e),
+            }
+        }
+    }
+
+    #[test]
+    fn reports_errors_with_their_column() {
+        let cases = [
+            ("cmd > a b", 8, "unexpected 'b' after redirect"),
+            ("echo 'hi", 5, "unterminated single quote"),
+            ("echo \"hi", 5, "unterminated double quote"),
+            ("echo \\", 5, "trailing backslash"),
+            ("echo ${HOME", 5, "unterminated '${'"),
+            ("echo ${1x}", 5, "bad substitution '${1x}'"),
+            ("| wc", 0, "expected a command"),
+            ("ls | | wc", 5, "expected a command"),
+            ("ls ;; wc", 4, "expected a command"),
+            ("echo a &&", 7, "expected a command after '&&'"),
+            ("sleep 1 &", 8, "background jobs ('&') are not supported"),
+            ("echo >", 6, "expected a file name after redirect"),
+            ("echo > | wc", 7, "expected a file name, found '|'"),
+            ("echo > a > b", 11, "only one redirect per command"),
+        ];
+        for (line, column, message) in cases {
+            assert_eq!(parse(line, &no_aliases), Err(error(column, message)), "parsing {:?}", line);
+        }
+    }
+
+    #[test]
+    fn expands_aliases_at_the_start_of_commands() {
+        let aliases = |name: &str| match name {
+            "ll" => Some("ls -l".to_string()),
+            "ls" => Some("ls -F".to_string()),
+            "both" => Some("ll; ll".to_string()),
+            "broken" => Some("echo 'oops".to_string()),
+            _ => None,
+        };
+        let cases = [
+            ("ll -a", "[ls] [-F] [-l] [-a]"),
+            ("echo ll | ll", "[echo] [ll] | [ls] [-F] [-l]"),
+            ("'ll'", "[ll]"),
+            ("\\ll", "[ll]"),
+            ("both", "[ls] [-F] [-l]; [ls] [-F] [-l]"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(show(&parse(line, &aliases).unwrap()), expected, "parsing {:?}", line);
+        }
+        assert_eq!(parse("echo; broken", &aliases), Err(error(6, "in alias 'broken': unterminated single quote")));
+    }
+
+    #[test]
+    fn expands_variables_into_one_word() {
+        let script = parse("echo \"$A\"b$B", &no_aliases).unwrap();
+        let word = &script.items[0].1.commands[0].argv[1];
+        assert_eq!(word.expand(|name| Some(format!("<{} x>", name))), "<A x>b<B x>");
+        assert_eq!(word.expand(|_| None), "b");
+    }
+}
@@ -0,0 +1,311 @@
+//! The in-memory filesystem the terminal explores.
+//!
+//! [`layout::build`] lays the resume, gallery and shaders out as files; the
+//! commands in [`commands`] (`ls`, `cd`, `cat`, ...) work on a shared [`Vfs`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::resume::Resume;
+
+pub mod commands;
+pub mod layout;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    File(String),
+    Dir(Dir),
+}
+
+/// A directory's entries, sorted by name.
+pub type Dir = BTreeMap<String, Node>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    NotFound(String),
+    NotADirectory(String),
+    IsADirectory(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound(path) => write!(f, "{}: No such file or directory", path),
+            Error::NotADirectory(path) => write!(f, "{}: Not a directory", path),
+            Error::IsADirectory(path) => write!(f, "{}: Is a directory", path),
+        }
+    }
+}
+
+/// A directory tree plus the working directory.
+#[derive(Debug, Clone)]
+pub struct Vfs {
+    /// Always a `Node::Dir`.
+    root: Node,
+    /// Always absolute and normalized, like everything `resolve` returns.
+    cwd: String,
+    /// What [`layout::RESUME`] was rendered from.
+    resume: Option<Resume>,
+}
+
+impl Default for Vfs {
+    fn default() -> Self {
+        Vfs::new(Dir::new())
+    }
+}
+
+impl Vfs {
+    pub fn new(root: Dir) -> Self {
+        Vfs { root: Node::Dir(root), cwd: "/".to_string(), resume: None }
+    }
+
+    /// Keeps `resume` so `cat` can render [`layout::RESUME`] again at the terminal's width.
+    pub fn set_resume(&mut self, resume: Resume) {
+        self.resume = Some(resume);
+    }
+
+    pub fn resume(&self) -> Option<&Resume> {
+        self.resume.as_ref()
+    }
+
+    /// Swaps in a new tree, staying in the working directory if it still exists.
+    pub fn replace_root(&mut self, root: Dir) {
+        self.root = Node::Dir(root);
+        if self.dir(&self.cwd).is_err() {
+            self.cwd = "/".to_string();
+        }
+    }
+
+    pub fn cwd(&self) -> &str {
+        &self.cwd
+    }
+
+    /// Makes `path` absolute and removes `.`, `..` and repeated slashes. `~` is `/`.
+    pub fn resolve(&self, path: &str) -> String {
+        let (base, path) = match path.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => ("", rest),
+            _ if path.starts_with('/') => ("", path),
+            _ => (self.cwd.as_str(), path),
+        };
+        let mut parts: Vec<&str> = Vec::new();
+        for part in base.split('/').chain(path.split('/')) {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    parts.pop();
+                }
+                part => parts.push(part),
+            }
+        }
+        format!("/{}", parts.join("/"))
+    }
+
+    pub fn get(&self, path: &str) -> Result<&Node, Error> {
+        let mut node = &self.root;
+        for part in self.resolve(path).split('/').filter(|p| !p.is_empty()) {
+            node = match node {
+                Node::Dir(dir) => dir.get(part).ok_or_else(|| Error::NotFound(path.to_string()))?,
+                Node::File(_) => return Err(Error::NotADirectory(path.to_string())),
+            };
+        }
+        Ok(node)
+    }
+
+    /// The entries of the directory at `path`.
+    pub fn dir(&self, path: &str) -> Result<&Dir, Error> {
+        match self.get(path)? {
+            Node::Dir(dir) => Ok(dir),
+            Node::File(_) => Err(Error::NotADirectory(path.to_string())),
+        }
+    }
+
+    /// The contents of the file at `path`.
+    pub fn read(&self, path: &str) -> Result<&str, Error> {
+        match self.get(path)? {
+            Node::File(contents) => Ok(contents),
+            Node::Dir(_) => Err(Error::IsADirectory(path.to_string())),
+        }
+    }
+
+    pub fn cd(&mut self, path: &str) -> Result<(), Error> {
+        self.dir(path)?;
+        self.cwd = self.resolve(path);
+        Ok(())
+    }
+
+    /// Creates or overwrites (or with `append`, extends) the file at `path`.
+    /// Its directory must already exist.
+    pub fn write(&mut self, path: &str, contents: &str, append: bool) -> Result<(), Error> {
+        let resolved = self.resolve(path);
+        let (parent, name) = resolved.rsplit_once('/').unwrap_or(("", ""));
+        if name.is_empty() {
+            return Err(Error::IsADirectory(path.to_string()));
+        }
+        let mut node = &mut self.root;
+        for part in parent.split('/').filter(|p| !p.is_empty()) {
+            node = match node {
+                Node::Dir(dir) => dir.get_mut(part).ok_or_else(|| Error::NotFound(path.to_string()))?,
+                Node::File(_) => return Err(Error::NotADirectory(path.to_string())),
+            };
+        }
+        let Node::Dir(dir) = node else {
+            return Err(Error::NotADirectory(path.to_string()));
+        };
+        match dir.entry(name.to_string()).or_insert_with(|| Node::File(String::new())) {
+            Node::File(file) => {
+                if !append {
+                    file.clear();
+                }
+                file.push_str(contents);
+                if !file.is_empty() && !file.ends_with('\n') {
+                    file.push('\n');
+                }
+                Ok(())
+            }
+            Node::Dir(_) => Err(Error::IsADirectory(path.to_string())),
+        }
+    }
+
+    /// Every file and directory under `path` (itself excluded), depth first,
+    /// as paths that start with `path` as written.
+    pub fn walk(&self, path: &str) -> Result<Vec<(String, &Node)>, Error> {
+        fn visit<'a>(prefix: &str, dir: &'a Dir, out: &mut Vec<(String, &'a Node)>) {
+            for (name, node) in dir {
+                let path = format!("{}/{}", prefix.trim_end_matches('/'), name);
+                out.push((path.clone(), node));
+                if let Node::Dir(child) = node {
+                    visit(&path, child, out);
+                }
+            }
+        }
+        let mut out = Vec::new();
+        visit(path, self.dir(path)?, &mut out);
+        Ok(out)
+    }
+
+    /// Path completions for `partial`, keeping whatever directory part it was typed with.
+    pub fn complete(&self, partial: &str) -> Vec<String> {
+        let (dir, name) = match partial.rsplit_once('/') {
+            Some((dir, name)) => (Some(dir), name),
+            None => (None, partial),
+        };
+        let Ok(entries) = self.dir(match dir {
+            Some("") => "/",
+            Some(dir) => dir,
+            None => ".",
+        }) else {
+            return Vec::new();
+        };
+        entries
+            .iter()
+            .filter(|(entry, _)| entry.starts_with(name))
+            .map(|(entry, node)| {
+                let slash = if matches!(node, Node::Dir(_)) { "/" } else { "" };
+                match dir {
+                    Some(dir) => format!("{}/{}{}", dir, entry, slash),
+                    None => format!("{}{}", entry, slash),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `/a/b.txt`, `/a/c/`, `/a/c/d.md` and `/e`, with `cwd` as the working directory.
+    fn vfs(cwd: &str) -> Vfs {
+        let file = |text: &str| Node::File(text.to_string());
+        let c = Dir::from([("d.md".to_string(), file("deep\n"))]);
+        let a = Dir::from([("b.txt".to_string(), file("bee\n")), ("c".to_string(), Node::Dir(c))]);
+        let mut vfs = Vfs::new(Dir::from([("a".to_string(), Node::Dir(a)), ("e".to_string(), file("e\n"))]));
+        vfs.cd(cwd).unwrap();
+        vfs
+    }
+
+    #[test]
+    fn resolves_paths() {
+        let vfs = vfs("/a/c");
+        let cases = [
+            ("", "/a/c"),
+            (".", "/a/c"),
+            ("d.md", "/a/c/d.md"),
+            ("..", "/a"),
+            ("../b.txt", "/a/b.txt"),
+            ("../../../..", "/"),
+            ("/..", "/"),
+            ("/a/./c//d.md", "/a/c/d.md"),
+            ("c/", "/a/c/c"),
+            ("/a/", "/a"),
+            ("~", "/"),
+            ("~/e", "/e"),
+            ("~e", "/a/c/~e"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(vfs.resolve(path), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn reads_and_reports_what_is_missing() {
+        let vfs = vfs("/a");
+        assert_eq!(vfs.read("b.txt"), Ok("bee\n"));
+        assert_eq!(vfs.read("c"), Err(Error::IsADirectory("c".to_string())));
+        assert_eq!(vfs.read("nope"), Err(Error::NotFound("nope".to_string())));
+        assert_eq!(vfs.read("b.txt/x"), Err(Error::NotADirectory("b.txt/x".to_string())));
+        assert_eq!(vfs.dir("/e").unwrap_err().to_string(), "/e: Not a directory");
+    }
+
+    #[test]
+    fn writes_and_appends_whole_lines() {
+        let mut vfs = vfs("/a");
+        vfs.write("new", "one", false).unwrap();
+        assert_eq!(vfs.read("/a/new"), Ok("one\n"));
+        vfs.write("new", "two\n", true).unwrap();
+        assert_eq!(vfs.read("new"), Ok("one\ntwo\n"));
+        vfs.write("~/e", "", false).unwrap();
+        assert_eq!(vfs.read("/e"), Ok(""));
+
+        assert_eq!(vfs.write("c", "x", false), Err(Error::IsADirectory("c".to_string())));
+        assert_eq!(vfs.write("/", "x", false), Err(Error::IsADirectory("/".to_string())));
+        assert_eq!(vfs.write("missing/f", "x", false), Err(Error::NotFound("missing/f".to_string())));
+        assert_eq!(vfs.write("b.txt/f", "x", false), Err(Error::NotADirectory("b.txt/f".to_string())));
+    }
+
+    #[test]
+    fn walks_depth_first_from_the_path_as_written() {
+        let vfs = vfs("/");
+        let paths = |path: &str| vfs.walk(path).unwrap().into_iter().map(|(path, _)| path).collect::<Vec<String>>();
+        assert_eq!(paths("/"), ["/a", "/a/b.txt", "/a/c", "/a/c/d.md", "/e"]);
+        assert_eq!(paths("a/"), ["a/b.txt", "a/c", "a/c/d.md"]);
+        assert_eq!(paths("a/c"), ["a/c/d.md"]);
+        assert_eq!(vfs.walk("e"), Err(Error::NotADirectory("e".to_string())));
+    }
+
+    #[test]
+    fn completes_paths_as_typed() {
+        let vfs = vfs("/a");
+        let cases: [(&str, &[&str]); 7] = [
+            ("", &["b.txt", "c/"]),
+            ("c", &["c/"]),
+            ("c/", &["c/d.md"]),
+            ("/", &["/a/", "/e"]),
+            ("../", &["../a/", "../e"]),
+            ("~/a/b", &["~/a/b.txt"]),
+            ("nope/", &[]),
+        ];
+        for (partial, expected) in cases {
+            assert_eq!(vfs.complete(partial), expected, "{}", partial);
+        }
+    }
+
+    #[test]
+    fn replacing_the_root_keeps_the_working_directory_if_it_can() {
+        let mut vfs = vfs("/a/c");
+        vfs.replace_root(vfs.dir("/").unwrap().clone());
+        assert_eq!(vfs.cwd(), "/a/c");
+        vfs.replace_root(Dir::new());
+        assert_eq!(vfs.cwd(), "/");
+    }
+}
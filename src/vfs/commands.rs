@@ -0,0 +1,608 @@
+//! `ls`, `cd`, `pwd`, `cat`, `tree`, `head`, `tail` and `find` over a shared [`Vfs`].
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{layout, Node, Vfs};
+use crate::ansi;
+use crate::command::{usage_error, Arg, ArgKind, Command, Context, Registry, RunFuture, Value};
+use crate::resume::format::{format, Ansi, Format, PlainText};
+use crate::shell::filters::strs;
+use crate::shell::{Output, Terminal};
+
+/// Adds the filesystem commands to `registry`, all working on `vfs`.
+pub fn register(registry: &mut Registry, vfs: &Rc<RefCell<Vfs>>) {
+    registry.register(Ls(vfs.clone()));
+    registry.register(Cd(vfs.clone()));
+    registry.register(Pwd(vfs.clone()));
+    registry.register(Cat(vfs.clone()));
+    registry.register(Tree(vfs.clone()));
+    registry.register(Head(vfs.clone()));
+    registry.register(Tail(vfs.clone()));
+    registry.register(Find(vfs.clone()));
+}
+
+const PATH: Arg = Arg { name: "PATH", kind: ArgKind::Positional(Value::Path), help: "file or directory, relative to the working directory" };
+
+/// Concatenates the files at `paths`, or falls back to `stdin` when there are none.
+fn read_all(vfs: &Vfs, paths: &[&str], stdin: Option<&str>, command: &str) -> Output {
+    if paths.is_empty() {
+        return stdin.unwrap_or_default().into();
+    }
+    let mut out = Output::default();
+    let mut errors = Vec::new();
+    for path in paths {
+        match vfs.read(path) {
+            Ok(contents) => out.stdout.push_str(contents),
+            Err(e) => errors.push(format!("{}: {}", command, e)),
+        }
+        if !out.stdout.is_empty() && !out.stdout.ends_with('\n') {
+            out.stdout.push('\n');
+        }
+    }
+    if !errors.is_empty() {
+        out.stderr = errors.join("\n");
+        out.status = 1;
+    }
+    out
+}
+
+/// [`layout::RESUME`] laid out for `terminal`, unless the file has been overwritten.
+fn resume_for(vfs: &Vfs, terminal: Terminal) -> Option<String> {
+    let resume = vfs.resume()?;
+    if vfs.read(layout::RESUME).ok()? != layout::resume_text(resume) {
+        return None;
+    }
+    let width = Some(terminal.columns);
+    Some(if terminal.color { format(resume, &Ansi { width }) } else { format(resume, &PlainText { width }) })
+}
+
+/// `name/`, in bold blue if `color`.
+fn dir_name(name: &str, color: bool) -> String {
+    let name = format!("{}/", name);
+    if color {
+        ansi::paint(&[ansi::BOLD, ansi::BLUE], &name)
+    } else {
+        name
+    }
+}
+
+/// Splits a leading `-n N` off `args`.
+fn line_count<'a>(args: &'a [&'a str]) -> Result<(usize, &'a [&'a str]), ()> {
+    match args {
+        ["-n", n, rest @ ..] => n.parse().map(|n| (n, rest)).map_err(|_| ()),
+        ["-n"] => Err(()),
+        rest => Ok((10, rest)),
+    }
+}
+
+pub struct Ls(Rc<RefCell<Vfs>>);
+
+impl Command for Ls {
+    fn name(&self) -> &'static str {
+        "ls"
+    }
+
+    fn usage(&self) -> &'static str {
+        "ls [-l] [PATH...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "List a directory"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "-l", kind: ArgKind::Flag, help: "show type and size" }, PATH]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let args = strs(args);
+            let long = args.contains(&"-l");
+            let mut paths: Vec<&str> = args.iter().copied().filter(|a| *a != "-l").collect();
+            if paths.iter().any(|p| p.starts_with('-')) {
+                return usage_error(self);
+            }
+            if paths.is_empty() {
+                paths.push(".");
+            }
+
+            let vfs = self.0.borrow();
+            let mut out = Output::default();
+            let mut listings = Vec::new();
+            let mut errors = Vec::new();
+            for path in &paths {
+                let entries: Vec<(&str, &Node)> = match vfs.get(path) {
+                    Ok(Node::Dir(dir)) => dir.iter().map(|(name, node)| (name.as_str(), node)).collect(),
+                    Ok(file) => vec![(*path, file)],
+                    Err(e) => {
+                        errors.push(format!("ls: {}", e));
+                        continue;
+                    }
+                };
+                let lines: Vec<String> = entries
+                    .into_iter()
+                    .map(|(name, node)| match (node, long) {
+                        (Node::Dir(dir), true) => format!("dr-xr-xr-x {:>6} {}", dir.len(), dir_name(name, ctx.color())),
+                        (Node::File(contents), true) => format!("-rw-r--r-- {:>6} {}", contents.len(), name),
+                        (Node::Dir(_), false) => dir_name(name, ctx.color()),
+                        (Node::File(_), false) => name.to_string(),
+                    })
+                    .collect();
+                if paths.len() > 1 {
+                    listings.push(format!("{}:\n{}", path, lines.join("\n")));
+                } else {
+                    listings.push(lines.join("\n"));
+                }
+            }
+            out.stdout = listings.join("\n\n");
+            if !errors.is_empty() {
+                out.stderr = errors.join("\n");
+                out.status = 1;
+            }
+            out
+        })
+    }
+}
+
+pub struct Cd(Rc<RefCell<Vfs>>);
+
+impl Command for Cd {
+    fn name(&self) -> &'static str {
+        "cd"
+    }
+
+    fn usage(&self) -> &'static str {
+        "cd [PATH]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change the working directory (default /)"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[PATH]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let path = match strs(args).as_slice() {
+                [] => "/",
+                [path] => path,
+                _ => return usage_error(self),
+            };
+            match self.0.borrow_mut().cd(path) {
+                Ok(()) => Output::default(),
+                Err(e) => Output::error(format!("cd: {}", e)),
+            }
+        })
+    }
+}
+
+pub struct Pwd(Rc<RefCell<Vfs>>);
+
+impl Command for Pwd {
+    fn name(&self) -> &'static str {
+        "pwd"
+    }
+
+    fn usage(&self) -> &'static str {
+        "pwd"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the working directory"
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, _args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move { self.0.borrow().cwd().into() })
+    }
+}
+
+pub struct Cat(Rc<RefCell<Vfs>>);
+
+impl Command for Cat {
+    fn name(&self) -> &'static str {
+        "cat"
+    }
+
+    fn usage(&self) -> &'static str {
+        "cat [FILE...] | cat resume --format FORMAT"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print files, or the input"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "FILE", kind: ArgKind::Positional(Value::Path), help: "file to print; try `resume`" },
+            Arg { name: "--format", kind: ArgKind::Option(Value::OneOf(&Format::NAMES)), help: "with `resume`: how to render it" },
+        ]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let vfs = self.0.borrow();
+            match strs(args).as_slice() {
+                ["resume", "--format", name] => match name.parse::<Format>() {
+                    Ok(_) if vfs.resume().is_none() => Output::error("Error fetching resume"),
+                    Ok(format) => read_all(&vfs, &[&format!("/resume.{}", format.extension())], None, "cat"),
+                    Err(e) => Output::error(e),
+                },
+                paths if paths.iter().any(|p| p.starts_with('-')) => usage_error(self),
+                // `resume` is the resume from any directory, as it is with `--format`.
+                [path] if *path == "resume" || vfs.resolve(path) == layout::RESUME => {
+                    if vfs.resume().is_none() {
+                        return Output::error("Error fetching resume");
+                    }
+                    // The resume is laid out again to fit the terminal it's printed on.
+                    match ctx.terminal.and_then(|t| resume_for(&vfs, t)) {
+                        Some(text) => text.into(),
+                        None => read_all(&vfs, &[layout::RESUME], ctx.stdin, "cat"),
+                    }
+                }
+                paths => read_all(&vfs, paths, ctx.stdin, "cat"),
+            }
+        })
+    }
+}
+
+pub struct Tree(Rc<RefCell<Vfs>>);
+
+impl Command for Tree {
+    fn name(&self) -> &'static str {
+        "tree"
+    }
+
+    fn usage(&self) -> &'static str {
+        "tree [PATH]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show a directory and everything under it"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[PATH]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let path = match strs(args).as_slice() {
+                [] => ".",
+                [path] => path,
+                _ => return usage_error(self),
+            };
+            let vfs = self.0.borrow();
+            let dir = match vfs.dir(path) {
+                Ok(dir) => dir,
+                Err(e) => return Output::error(format!("tree: {}", e)),
+            };
+            let mut lines = vec![path.to_string()];
+            let (mut dirs, mut files) = (0, 0);
+            let mut stack = vec![(dir.iter().peekable(), String::new())];
+            while let Some((entries, indent)) = stack.last_mut() {
+                let Some((name, node)) = entries.next() else {
+                    stack.pop();
+                    continue;
+                };
+                let last = entries.peek().is_none();
+                let indent = indent.clone();
+                let branch = if last { "└── " } else { "├── " };
+                match node {
+                    Node::Dir(child) => {
+                        dirs += 1;
+                        lines.push(format!("{}{}{}", indent, branch, dir_name(name, ctx.color())));
+                        stack.push((child.iter().peekable(), format!("{}{}", indent, if last { "    " } else { "│   " })));
+                    }
+                    Node::File(_) => {
+                        files += 1;
+                        lines.push(format!("{}{}{}", indent, branch, name));
+                    }
+                }
+            }
+            lines.push(format!("\n{} directories, {} files", dirs, files));
+            lines.join("\n").into()
+        })
+    }
+}
+
+pub struct Head(Rc<RefCell<Vfs>>);
+
+impl Command for Head {
+    fn name(&self) -> &'static str {
+        "head"
+    }
+
+    fn usage(&self) -> &'static str {
+        "head [-n N] [FILE...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the first lines of files or the input"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "-n", kind: ArgKind::Option(Value::Text), help: "number of lines (default 10)" }, PATH]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let args = strs(args);
+            let Ok((n, paths)) = line_count(&args) else {
+                return usage_error(self);
+            };
+            let mut out = read_all(&self.0.borrow(), paths, ctx.stdin, "head");
+            out.stdout = out.stdout.lines().take(n).collect::<Vec<&str>>().join("\n");
+            out
+        })
+    }
+}
+
+pub struct Tail(Rc<RefCell<Vfs>>);
+
+impl Command for Tail {
+    fn name(&self) -> &'static str {
+        "tail"
+    }
+
+    fn usage(&self) -> &'static str {
+        "tail [-n N] [FILE...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the last lines of files or the input"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "-n", kind: ArgKind::Option(Value::Text), help: "number of lines (default 10)" }, PATH]
+    }
+
+    fn run<'a>(&'a self, ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let args = strs(args);
+            let Ok((n, paths)) = line_count(&args) else {
+                return usage_error(self);
+            };
+            let mut out = read_all(&self.0.borrow(), paths, ctx.stdin, "tail");
+            let lines: Vec<&str> = out.stdout.lines().collect();
+            out.stdout = lines[lines.len().saturating_sub(n)..].join("\n");
+            out
+        })
+    }
+}
+
+pub struct Find(Rc<RefCell<Vfs>>);
+
+/// Matches `name` against a glob with `*` and `?`.
+fn glob(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Classic two-pointer wildcard match, backtracking to the last `*`.
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+impl Command for Find {
+    fn name(&self) -> &'static str {
+        "find"
+    }
+
+    fn usage(&self) -> &'static str {
+        "find [PATH] [-name PATTERN] [-type f|d]"
+    }
+
+    fn description(&self) -> &'static str {
+        "List files under a directory, optionally filtered"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            PATH,
+            Arg { name: "-name", kind: ArgKind::Option(Value::Text), help: "only names matching a glob like '*.md'" },
+            Arg { name: "-type", kind: ArgKind::Option(Value::OneOf(&["f", "d"])), help: "only files (f) or directories (d)" },
+        ]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let args = strs(args);
+            let (path, mut rest) = match args.split_first() {
+                Some((path, rest)) if !path.starts_with('-') => (*path, rest),
+                _ => (".", args.as_slice()),
+            };
+            let (mut name, mut kind) = (None, None);
+            loop {
+                match rest {
+                    [] => break,
+                    ["-name", pattern, tail @ ..] => {
+                        name = Some(*pattern);
+                        rest = tail;
+                    }
+                    ["-type", t @ ("f" | "d"), tail @ ..] => {
+                        kind = Some(*t);
+                        rest = tail;
+                    }
+                    _ => return usage_error(self),
+                }
+            }
+
+            let vfs = self.0.borrow();
+            let entries = match vfs.walk(path) {
+                Ok(entries) => entries,
+                Err(e) => return Output::error(format!("find: {}", e)),
+            };
+            let found: Vec<String> = std::iter::once((path.to_string(), None))
+                .chain(entries.into_iter().map(|(path, node)| (path, Some(node))))
+                .filter(|(path, node)| {
+                    let is_dir = node.is_none_or(|node| matches!(node, Node::Dir(_)));
+                    let base = path.rsplit('/').next().unwrap_or(path);
+                    kind.is_none_or(|k| (k == "d") == is_dir) && name.is_none_or(|pattern| glob(pattern, base))
+                })
+                .map(|(path, _)| path)
+                .collect();
+            found.join("\n").into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resume::Resume;
+    use crate::shell::block_on;
+    use crate::vfs::Dir;
+
+    fn resume() -> Resume {
+        Resume::from_json_str(include_str!("../../www/resume.json")).unwrap()
+    }
+
+    /// The site's filesystem, without gallery or shaders.
+    fn site() -> Rc<RefCell<Vfs>> {
+        let resume = resume();
+        let mut vfs = Vfs::new(layout::build(&resume, &[], &[]));
+        vfs.set_resume(resume);
+        Rc::new(RefCell::new(vfs))
+    }
+
+    /// `/docs/a.md`, `/docs/old/b.md` and `/notes`.
+    fn small() -> Rc<RefCell<Vfs>> {
+        let file = |text: &str| Node::File(text.to_string());
+        let old = Dir::from([("b.md".to_string(), file("b\n"))]);
+        let docs = Dir::from([("a.md".to_string(), file("a\n")), ("old".to_string(), Node::Dir(old))]);
+        let root = Dir::from([("docs".to_string(), Node::Dir(docs)), ("notes".to_string(), file("1\n2\n3\n4\n5\n"))]);
+        Rc::new(RefCell::new(Vfs::new(root)))
+    }
+
+    /// Runs `line` (split on spaces) on `vfs`, with its stdout on `terminal`.
+    fn run(vfs: &Rc<RefCell<Vfs>>, line: &str, terminal: Option<Terminal>) -> Output {
+        let mut registry = Registry::new();
+        register(&mut registry, vfs);
+        let argv: Vec<String> = line.split(' ').map(str::to_string).collect();
+        block_on(registry.run(&argv, None, terminal))
+    }
+
+    #[test]
+    fn globs_match_whole_names() {
+        let cases = [
+            ("*", "anything", true),
+            ("*", "", true),
+            ("*.md", "a.md", true),
+            ("*.md", "a.md.txt", false),
+            ("a?c", "abc", true),
+            ("a?c", "ac", false),
+            ("*a*b*", "xxaxxbxx", true),
+            ("*a*b", "xxbxxa", false),
+            ("a*a", "aaa", true),
+            ("exact", "exact", true),
+            ("exact", "exactly", false),
+        ];
+        for (pattern, name, expected) in cases {
+            assert_eq!(glob(pattern, name), expected, "{} {}", pattern, name);
+        }
+    }
+
+    #[test]
+    fn splits_off_a_line_count() {
+        let ok: [(&[&str], usize, &[&str]); 4] =
+            [(&[], 10, &[]), (&["f"], 10, &["f"]), (&["-n", "3"], 3, &[]), (&["-n", "0", "f", "g"], 0, &["f", "g"])];
+        for (args, n, rest) in ok {
+            assert_eq!(line_count(args), Ok((n, rest)), "{:?}", args);
+        }
+        for args in [&["-n"][..], &["-n", "x"], &["-n", "-1", "f"]] {
+            assert_eq!(line_count(args), Err(()), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn head_and_tail_take_lines() {
+        let vfs = small();
+        assert_eq!(run(&vfs, "head -n 2 notes", None).stdout, "1\n2");
+        assert_eq!(run(&vfs, "tail -n 2 notes", None).stdout, "4\n5");
+        assert_eq!(run(&vfs, "tail -n 9 notes", None).stdout, "1\n2\n3\n4\n5");
+        assert_eq!(run(&vfs, "head -n x notes", None).stderr, "usage: head [-n N] [FILE...]");
+        assert_eq!(run(&vfs, "tail -n", None).stderr, "usage: tail [-n N] [FILE...]");
+        let missing = run(&vfs, "head notes nope", None);
+        assert_eq!((missing.stdout.as_str(), missing.stderr.as_str(), missing.status), ("1\n2\n3\n4\n5", "head: nope: No such file or directory", 1));
+    }
+
+    #[test]
+    fn tree_draws_connectors_and_counts() {
+        let expected = ".
+├── docs/
+│   ├── a.md
+│   └── old/
+│       └── b.md
+└── notes
+
+2 directories, 3 files";
+        assert_eq!(run(&small(), "tree", None).stdout, expected);
+        assert_eq!(run(&small(), "tree docs/old", None).stdout, "docs/old\n└── b.md\n\n0 directories, 1 files");
+        assert_eq!(run(&small(), "tree notes", None).stderr, "tree: notes: Not a directory");
+    }
+
+    #[test]
+    fn finds_by_name_and_type() {
+        let vfs = small();
+        assert_eq!(run(&vfs, "find", None).stdout, ".\n./docs\n./docs/a.md\n./docs/old\n./docs/old/b.md\n./notes");
+        assert_eq!(run(&vfs, "find / -name *.md", None).stdout, "/docs/a.md\n/docs/old/b.md");
+        assert_eq!(run(&vfs, "find docs -type d", None).stdout, "docs\ndocs/old");
+        assert_eq!(run(&vfs, "find -type x", None).stderr, "usage: find [PATH] [-name PATTERN] [-type f|d]");
+    }
+
+    #[test]
+    fn cat_renders_the_resume_in_any_format() {
+        let vfs = site();
+        for format in Format::ALL {
+            let path = format!("/resume.{}", format.extension());
+            let expected = vfs.borrow().read(&path).unwrap().trim_end().to_string();
+            assert_eq!(run(&vfs, &format!("cat resume --format {}", format.extension()), None).stdout.trim_end(), expected);
+        }
+        assert_eq!(run(&vfs, "cat resume --format doc", None).status, 1);
+    }
+
+    #[test]
+    fn cat_lays_the_resume_out_for_the_terminal() {
+        let vfs = site();
+        let narrow = Terminal { columns: 40, color: false };
+        let expected = format(&resume(), &PlainText { width: Some(40) });
+        assert_eq!(run(&vfs, "cat /resume", Some(narrow)).stdout, expected);
+        // `resume` means the resume from anywhere.
+        run(&vfs, "cd experience", None);
+        assert_eq!(run(&vfs, "cat resume", Some(narrow)).stdout, expected);
+        assert!(run(&vfs, "cat resume", Some(Terminal::default())).stdout.contains("\x1b["));
+        // Piped, it's the file as it is.
+        assert_eq!(run(&vfs, "cat resume", None).stdout, layout::resume_text(&resume()));
+    }
+
+    #[test]
+    fn cat_prints_an_overwritten_resume_as_written() {
+        let vfs = site();
+        vfs.borrow_mut().write(layout::RESUME, "mine", false).unwrap();
+        assert_eq!(run(&vfs, "cat resume", Some(Terminal::default())).stdout, "mine\n");
+    }
+
+    #[test]
+    fn cat_reports_a_resume_that_failed_to_load() {
+        let vfs = small();
+        assert_eq!(run(&vfs, "cat resume", Some(Terminal::default())).stderr, "Error fetching resume");
+        assert_eq!(run(&vfs, "cat resume --format md", None).stderr, "Error fetching resume");
+    }
+}
@@ -0,0 +1,210 @@
+//! How the resume, gallery and shaders appear as files.
+//!
+//! ```text
+//! /README                       how to get around
+//! /contact
+//! /resume                       plain text; `cat` re-renders it for the terminal
+//! /resume.{txt,md,html,tex}
+//! /summary
+//! /experience/<company>.md
+//! /projects/<name>/README
+//! /skills/<category>            one skill per line
+//! /education/<university>.md
+//! /certifications/<name>.md
+//! /gallery/<media file>         title, description and URL
+//! /shaders/<name>.wgsl
+//! /shaders/lib/<name>.wgsl      code the shaders #include
+//! ```
+
+use super::{Dir, Node};
+use crate::gallery::{GalleryItem, MediaKind};
+use crate::resume::format::{contact_items, Entry, Format, Formatter, Markdown};
+use crate::resume::{enabled, Resume};
+
+/// Where the plain-text resume lives.
+pub const RESUME: &str = "/resume";
+
+/// The contents of [`RESUME`].
+pub fn resume_text(resume: &Resume) -> String {
+    Format::Text.render(resume, Some(80))
+}
+
+/// Builds the root directory. `shaders` is `(name, source)` as embedded by
+/// build.rs, where a name may have a directory, like `lib/noise`.
+pub fn build(resume: &Resume, gallery: &[GalleryItem], shaders: &[(&str, &str)]) -> Dir {
+    let mut root = Dir::new();
+    let sections = &resume.sections;
+
+    file(&mut root, "README", readme(resume));
+    file(&mut root, "contact", contact_items(&resume.contact).into_iter().map(|(text, _)| text).collect::<Vec<&str>>().join("\n"));
+    file(&mut root, &RESUME[1..], resume_text(resume));
+    for format in Format::ALL {
+        file(&mut root, &format!("resume.{}", format.extension()), format.render(resume, Some(80)));
+    }
+
+    if enabled(&sections.summary).is_some() && !resume.summary.is_empty() {
+        file(&mut root, "summary", resume.summary.clone());
+    }
+    if enabled(&sections.experience).is_some() {
+        let dir = subdir(&mut root, "experience");
+        for e in &resume.experience {
+            unique_file(dir, &slug(&e.company), ".md", entry(&Entry::experience(e)));
+        }
+    }
+    if enabled(&sections.projects).is_some() {
+        let dir = subdir(&mut root, "projects");
+        for p in &resume.projects {
+            let mut project = Dir::new();
+            file(&mut project, "README", entry(&Entry::project(p)));
+            insert_unique(dir, &slug(&p.name), "", Node::Dir(project));
+        }
+    }
+    if let Some(section) = enabled(&sections.skills) {
+        let dir = subdir(&mut root, "skills");
+        for (key, skills) in resume.skills.categories() {
+            // Same rule as the formatters: only labelled categories are shown.
+            if section.categories.contains_key(key) {
+                file(dir, key, skills.join("\n"));
+            }
+        }
+    }
+    if enabled(&sections.education).is_some() {
+        let dir = subdir(&mut root, "education");
+        for e in &resume.education {
+            unique_file(dir, &slug(&e.university), ".md", entry(&Entry::education(e)));
+        }
+    }
+    if enabled(&sections.certifications).is_some() && !resume.certifications.is_empty() {
+        let dir = subdir(&mut root, "certifications");
+        for c in &resume.certifications {
+            unique_file(dir, &slug(&c.name), ".md", entry(&Entry::certification(c)));
+        }
+    }
+
+    let dir = subdir(&mut root, "gallery");
+    for item in gallery {
+        let name = item.src.rsplit('/').next().unwrap_or(&item.src);
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) => (stem.to_string(), format!(".{}", extension)),
+            None => (name.to_string(), String::new()),
+        };
+        let kind = match item.kind {
+            MediaKind::Image => "image",
+            MediaKind::Video => "video",
+        };
+        unique_file(dir, &stem, &extension, format!("{}\n{}\n\n{}: {}", item.title, item.description, kind, item.src));
+    }
+
+    let shader_dir = subdir(&mut root, "shaders");
+    for (name, source) in shaders {
+        // Library names like `lib/noise` get their own directory.
+        let (dir, name) = match name.rsplit_once('/') {
+            Some((dir, name)) => (dir.split('/').fold(&mut *shader_dir, subdir), name),
+            None => (&mut *shader_dir, *name),
+        };
+        file(dir, &format!("{}.wgsl", name), source.to_string());
+    }
+    root
+}
+
+fn readme(resume: &Resume) -> String {
+    let mut out = resume.contact.name.clone();
+    if let Some(title) = &resume.contact.title {
+        out.push_str(&format!(" - {}", title));
+    }
+    out.push_str(
+        "\n\nThis resume is a filesystem. Some places to start:\n\n  \
+         ls                      what's here\n  \
+         tree                    everything at once\n  \
+         cat experience/<tab>    tab completes paths\n  \
+         cd projects; ls\n  \
+         find / -name '*.wgsl'   the shaders behind the background\n",
+    );
+    out
+}
+
+fn entry(entry: &Entry) -> String {
+    let mut out = String::new();
+    Markdown.entry(&mut out, entry);
+    out
+}
+
+fn file(dir: &mut Dir, name: &str, contents: String) {
+    dir.insert(name.to_string(), Node::File(contents));
+}
+
+fn subdir<'a>(dir: &'a mut Dir, name: &str) -> &'a mut Dir {
+    match dir.entry(name.to_string()).or_insert_with(|| Node::Dir(Dir::new())) {
+        Node::Dir(dir) => dir,
+        Node::File(_) => unreachable!("{} is only ever created as a directory", name),
+    }
+}
+
+fn unique_file(dir: &mut Dir, stem: &str, extension: &str, contents: String) {
+    insert_unique(dir, stem, extension, Node::File(contents));
+}
+
+/// Inserts `node` as `stem + extension`, or `stem-2 + extension` and so on if taken.
+fn insert_unique(dir: &mut Dir, stem: &str, extension: &str, node: Node) {
+    let mut name = format!("{}{}", stem, extension);
+    let mut n = 2;
+    while dir.contains_key(&name) {
+        name = format!("{}-{}{}", stem, n, extension);
+        n += 1;
+    }
+    dir.insert(name, node);
+}
+
+/// `Studio Todos` -> `studio-todos`.
+pub fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::Vfs;
+
+    #[test]
+    fn slugs_names() {
+        let cases = [("Studio Todos", "studio-todos"), ("  C++ & Rust!  ", "c-rust"), ("Üni Köln", "üni-köln"), ("???", "untitled")];
+        for (text, expected) in cases {
+            assert_eq!(slug(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn lays_out_the_resume_gallery_and_shaders() {
+        let resume = Resume::from_json_str(include_str!("../../www/resume.json")).unwrap();
+        let item = |src: &str, kind| GalleryItem { kind, src: src.to_string(), title: "Title".to_string(), description: "Words".to_string() };
+        let gallery = [item("gallery_media/demo.png", MediaKind::Image), item("other/demo.png", MediaKind::Video), item("noext", MediaKind::Image)];
+        let shaders = [("fire", "// fire"), ("lib/noise", "// noise")];
+        let vfs = Vfs::new(build(&resume, &gallery, &shaders));
+
+        assert_eq!(vfs.read(RESUME), Ok(resume_text(&resume).as_str()));
+        for format in Format::ALL {
+            assert!(vfs.read(&format!("/resume.{}", format.extension())).is_ok(), "{:?}", format);
+        }
+        assert!(vfs.read("/README").unwrap().starts_with(&resume.contact.name));
+        assert_eq!(vfs.read("/gallery/demo.png"), Ok("Title\nWords\n\nimage: gallery_media/demo.png"));
+        assert_eq!(vfs.read("/gallery/demo-2.png"), Ok("Title\nWords\n\nvideo: other/demo.png"));
+        assert!(vfs.read("/gallery/noext").is_ok());
+        assert_eq!(vfs.read("/shaders/fire.wgsl"), Ok("// fire"));
+        assert_eq!(vfs.read("/shaders/lib/noise.wgsl"), Ok("// noise"));
+        assert_eq!(vfs.dir("/experience").unwrap().len(), resume.experience.len());
+        assert!(vfs.dir("/experience").unwrap().keys().all(|name| name.ends_with(".md")));
+    }
+}
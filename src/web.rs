@@ -0,0 +1,456 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::command::{Command, CommandResult, Registry};
+use crate::session::{self, Session};
+use crate::shader::load::Diagnostic;
+use crate::shader::transition::{Kind, Transition};
+use crate::shell::{self, filters, Output, Terminal};
+use crate::vfs::{self, Vfs};
+use crate::{fetch, renderer, resume, shader};
+
+mod commands;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Fills the shader dropdown; each entry is `[id, name, description]`.
+    fn setup_shader_switcher(shaders: js_sys::Array);
+}
+
+// --- Global State for the Renderer (Single-Threaded) ---
+thread_local! {
+    static RENDER_STATE: RefCell<Option<renderer::State>> = const { RefCell::new(None) };
+}
+
+// --- WebGPU Renderer and Animation Loop ---
+fn request_animation_frame(f: &Closure<dyn FnMut(f64)>) {
+    web_sys::window()
+        .unwrap()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame` OK");
+}
+
+#[wasm_bindgen(start)]
+pub async fn start() {
+    console_error_panic_hook::set_once();
+    restore_session();
+    wasm_bindgen_futures::spawn_local(load_vfs());
+
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let canvas = document.get_element_by_id("wgpu-canvas").unwrap();
+    let canvas: web_sys::HtmlCanvasElement = canvas.dyn_into().unwrap();
+
+    // Set canvas size to window size
+    canvas.set_width(window.inner_width().unwrap().as_f64().unwrap() as u32);
+    canvas.set_height(window.inner_height().unwrap().as_f64().unwrap() as u32);
+
+    web_sys::console::log_1(&"Creating WebGPU renderer state...".into());
+    let shader = SESSION.with(|cell| cell.borrow().var("SHADER").map(str::to_string));
+    let state = match renderer::State::new(canvas.clone(), shader.as_deref()).await {
+        Ok(state) => state,
+        Err(e) => {
+            // The terminal and resume don't need the renderer; only the background goes.
+            web_sys::console::warn_2(&"No shader background:".into(), &e.to_string().into());
+            static_background(&document);
+            return;
+        }
+    };
+    web_sys::console::log_1(&"WebGPU renderer state created successfully".into());
+
+    update_shader_switcher(&state);
+    RENDER_STATE.with(|cell| *cell.borrow_mut() = Some(state));
+
+    // Handle resize
+    let canvas_clone = canvas.clone();
+    let resize_closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+        let window = web_sys::window().unwrap();
+        let width = window.inner_width().unwrap().as_f64().unwrap() as u32;
+        let height = window.inner_height().unwrap().as_f64().unwrap() as u32;
+        
+        canvas_clone.set_width(width);
+        canvas_clone.set_height(height);
+        
+        RENDER_STATE.with(|cell| {
+            if let Ok(mut borrow) = cell.try_borrow_mut() {
+                if let Some(state) = borrow.as_mut() {
+                    state.resize((width, height));
+                }
+            }
+        });
+    }) as Box<dyn FnMut(_)>);
+    
+    window.add_event_listener_with_callback("resize", resize_closure.as_ref().unchecked_ref()).unwrap();
+    resize_closure.forget();
+
+    let f = Rc::new(RefCell::new(None));
+    let g = f.clone();
+
+    let mouse_pos = Rc::new(RefCell::new([0.0, 0.0]));
+    let mouse_pos_clone = mouse_pos.clone();
+
+    let mouse_closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        let mut pos = mouse_pos_clone.borrow_mut();
+        pos[0] = event.client_x() as f32;
+        pos[1] = event.client_y() as f32;
+    }) as Box<dyn FnMut(_)>);
+    window.add_event_listener_with_callback("mousemove", mouse_closure.as_ref().unchecked_ref()).unwrap();
+    mouse_closure.forget();
+
+    *g.borrow_mut() = Some(Closure::wrap(Box::new(move |time: f64| {
+        RENDER_STATE.with(|state_cell| {
+            if let Ok(mut borrow) = state_cell.try_borrow_mut() {
+                if let Some(state) = borrow.as_mut() {
+                    state.update(time as f32 / 1000.0, *mouse_pos.borrow());
+                    match state.render() {
+                        Ok(_) => {}
+                        Err(wgpu::SurfaceError::Lost) => state.resize(state.size),
+                        Err(e) => eprintln!("Error rendering frame: {:?}", e),
+                    }
+                }
+            }
+        });
+        request_animation_frame(f.borrow().as_ref().unwrap());
+    }) as Box<dyn FnMut(_)>));
+
+    request_animation_frame(g.borrow().as_ref().unwrap());
+}
+
+/// Swaps the canvas for the plain CSS background index.html styles under
+/// `.static-background`.
+fn static_background(document: &web_sys::Document) {
+    if let Some(root) = document.document_element() {
+        let _ = root.class_list().add_1("static-background");
+    }
+}
+
+/// Fills the shader dropdown with the visible shaders.
+fn update_shader_switcher(state: &renderer::State) {
+    let js_shaders = js_sys::Array::new();
+    for info in state.shaders().filter(|info| !info.hidden) {
+        let entry = [&info.id, &info.name, &info.description].iter().map(|s| JsValue::from_str(s)).collect::<js_sys::Array>();
+        js_shaders.push(&entry);
+    }
+    setup_shader_switcher(js_shaders);
+}
+
+// --- Terminal Command Logic ---
+/// Switches the background to shader `name`. Without a transition or a
+/// duration, it uses the default transition's.
+#[wasm_bindgen]
+pub fn set_shader(name: String, transition: Option<String>, duration_ms: Option<u32>) {
+    let mut switch = Transition::default();
+    if let Some(kind) = transition.as_deref().and_then(Kind::parse) {
+        switch.kind = kind;
+    }
+    if let Some(ms) = duration_ms {
+        switch.duration = ms as f32 / 1000.0;
+    }
+    RENDER_STATE.with(|cell| {
+        if let Ok(mut borrow) = cell.try_borrow_mut() {
+            if let Some(state) = borrow.as_mut() {
+                state.set_pipeline(&name, switch);
+            }
+        }
+    });
+}
+
+#[wasm_bindgen]
+pub fn get_active_shader() -> String {
+    RENDER_STATE.with(|cell| {
+        if let Ok(borrow) = cell.try_borrow() {
+            if let Some(state) = borrow.as_ref() {
+                return state.get_active_shader();
+            }
+        }
+        "none".to_string()
+    })
+}
+
+/// Compiles `source` as shader `name`, adding it or replacing the shader of
+/// that name. On failure it rejects with an array of `{file, line, message,
+/// report}` diagnostics and whatever was running keeps running.
+#[wasm_bindgen]
+pub async fn load_shader(name: String, source: String) -> Result<(), JsValue> {
+    load(&name, &source, &shader::load::embedded_library)
+        .await
+        .map_err(|diagnostics| serde_wasm_bindgen::to_value(&diagnostics).unwrap_or_else(JsValue::from))
+}
+
+async fn load(id: &str, source: &str, library: &dyn Fn(&str) -> Option<String>) -> Result<(), Vec<Diagnostic>> {
+    let pending = RENDER_STATE.with(|cell| match cell.borrow().as_ref() {
+        Some(state) => state.compile_shader(id, source, library),
+        None => Err(vec![Diagnostic::new(&format!("{}.wgsl", id), None, "the renderer failed to start")]),
+    })?;
+    let compiled = pending.finish().await?;
+    RENDER_STATE.with(|cell| {
+        if let Some(state) = cell.borrow_mut().as_mut() {
+            state.install(compiled);
+            update_shader_switcher(state);
+        }
+    });
+    // So `cat` shows what's running; before the filesystem loads there's nowhere to put it.
+    VFS.with(|vfs| {
+        let _ = vfs.borrow_mut().write(&format!("/shaders/{}.wgsl", id), source, false);
+    });
+    Ok(())
+}
+
+/// For development: every `interval_ms`, fetches each shader and library
+/// file from `./shaders/` (run.sh links `src/shaders` there) and reloads
+/// the shaders that changed, or all of them if a library file did. Errors
+/// go to the console. New files need a rebuild.
+#[wasm_bindgen]
+pub fn watch_shaders(interval_ms: u32) {
+    let mut shaders: BTreeMap<String, String> = shader::SHADERS.iter().map(|info| (info.id.to_string(), info.source.to_string())).collect();
+    let mut library: BTreeMap<String, String> = shader::LIBRARY.iter().map(|(path, source)| (path.to_string(), source.to_string())).collect();
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut warned = false;
+        loop {
+            sleep(interval_ms).await;
+            let mut library_changed = false;
+            for (path, text) in library.iter_mut() {
+                match fetch_shader_file(path, &mut warned).await {
+                    Some(latest) if latest != *text => {
+                        *text = latest;
+                        library_changed = true;
+                    }
+                    _ => {}
+                }
+            }
+            for (id, text) in shaders.iter_mut() {
+                let Some(latest) = fetch_shader_file(&format!("{}.wgsl", id), &mut warned).await else {
+                    continue;
+                };
+                if latest == *text && !library_changed {
+                    continue;
+                }
+                *text = latest;
+                match load(id, text, &|path| library.get(path).cloned()).await {
+                    Ok(()) => web_sys::console::log_2(&"Reloaded shader".into(), &id.into()),
+                    Err(diagnostics) => {
+                        for diagnostic in diagnostics {
+                            web_sys::console::error_1(&diagnostic.to_string().into());
+                            if let Some(report) = diagnostic.report {
+                                web_sys::console::error_1(&report.into());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// `path` under `./shaders/`, past any cache; warns the first time one can't be fetched.
+async fn fetch_shader_file(path: &str, warned: &mut bool) -> Option<String> {
+    match fetch::fetch_text(&format!("./shaders/{}?t={}", path, js_sys::Date::now())).await {
+        Ok(text) => Some(text),
+        Err(e) => {
+            if !*warned {
+                web_sys::console::warn_2(&"Can't watch shaders; is src/shaders served as www/shaders?".into(), &e);
+                *warned = true;
+            }
+            None
+        }
+    }
+}
+
+async fn sleep(ms: u32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window().unwrap().set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms as i32).unwrap();
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Renders `resume.json` to PDF; JS only has to save the returned bytes.
+#[wasm_bindgen]
+pub async fn resume_pdf() -> Result<Vec<u8>, JsValue> {
+    let resume = fetch::fetch_resume().await?;
+    Ok(resume::pdf::render(&resume))
+}
+
+thread_local! {
+    static VFS: Rc<RefCell<Vfs>> = Rc::new(RefCell::new(Vfs::default()));
+    /// The load [`load_vfs`] waits on, started by whichever call comes first.
+    static VFS_LOAD: RefCell<Option<js_sys::Promise>> = const { RefCell::new(None) };
+    static SESSION: Rc<RefCell<Session>> = Rc::new(RefCell::new(Session::default()));
+    static REGISTRY: RefCell<Rc<Registry>> = RefCell::new(Rc::new(builtin_registry()));
+}
+
+/// The localStorage key the session is saved under.
+const SESSION_KEY: &str = "terminal-session";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Picks up the history, variables and aliases saved by an earlier visit.
+fn restore_session() {
+    let Some(json) = local_storage().and_then(|storage| storage.get_item(SESSION_KEY).ok().flatten()) else {
+        return;
+    };
+    match Session::from_json(&json) {
+        Ok(session) => SESSION.with(|cell| *cell.borrow_mut() = session),
+        Err(e) => web_sys::console::warn_2(&"Ignoring the saved terminal session:".into(), &e.to_string().into()),
+    }
+}
+
+fn save_session() {
+    let json = SESSION.with(|cell| cell.borrow().to_json());
+    if let Some(storage) = local_storage() {
+        // Private browsing or a full quota only costs the history.
+        let _ = storage.set_item(SESSION_KEY, &json);
+    }
+}
+
+fn builtin_registry() -> Registry {
+    let mut registry = Registry::new();
+    filters::register(&mut registry);
+    VFS.with(|vfs| vfs::commands::register(&mut registry, vfs));
+    SESSION.with(|session| session::commands::register(&mut registry, session));
+    commands::register(&mut registry);
+    registry
+}
+
+/// Builds the filesystem from `resume.json` and `gallery.json` the first time it's needed.
+///
+/// Every caller waits on the same load, so a command typed while it's running
+/// doesn't fetch again, or swap in a second tree over what it has written since.
+async fn load_vfs() {
+    let load = VFS_LOAD.with(|load| {
+        let mut load = load.borrow_mut();
+        let promise = load.get_or_insert_with(|| {
+            wasm_bindgen_futures::future_to_promise(async {
+                build_vfs().await;
+                Ok(JsValue::UNDEFINED)
+            })
+        });
+        promise.clone()
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(load).await;
+}
+
+async fn build_vfs() {
+    let resume = match fetch::fetch_resume().await {
+        Ok(resume) => resume,
+        Err(e) => {
+            web_sys::console::warn_2(&"Could not load resume.json for the terminal:".into(), &e);
+            // Try again on the next command.
+            VFS_LOAD.with(|load| *load.borrow_mut() = None);
+            return;
+        }
+    };
+    let gallery = fetch::fetch_gallery().await.unwrap_or_else(|e| {
+        web_sys::console::warn_2(&"Could not load gallery.json for the terminal:".into(), &e);
+        Vec::new()
+    });
+    let library = shader::LIBRARY.iter().map(|(path, source)| (path.trim_end_matches(".wgsl"), *source));
+    let shaders: Vec<(&str, &str)> = std::iter::once(("vs", shader::VERTEX_SHADER))
+        .chain(shader::SHADERS.iter().map(|info| (&*info.id, &*info.source)))
+        .chain(library)
+        .collect();
+    let root = vfs::layout::build(&resume, &gallery, &shaders);
+    VFS.with(|vfs| {
+        let mut vfs = vfs.borrow_mut();
+        vfs.replace_root(root);
+        vfs.set_resume(resume);
+    });
+}
+
+/// Adds a command to the terminal, replacing any built-in with the same name.
+///
+/// This is the hook for crates embedding the resume page to bring their own commands.
+pub fn register_command(command: impl Command + 'static) {
+    REGISTRY.with(|registry| Rc::make_mut(&mut registry.borrow_mut()).register(command));
+}
+
+/// Runs one terminal line for a terminal `columns` wide; the returned
+/// `CommandResult` is a plain object with `output` and a list of `effects` tagged by `type`.
+#[wasm_bindgen]
+pub async fn run_command(command: String, columns: usize) -> Result<JsValue, JsValue> {
+    load_vfs().await;
+    let line = match SESSION.with(|cell| cell.borrow_mut().record(&command)) {
+        Ok(line) => line,
+        Err(e) => return serde_wasm_bindgen::to_value(&CommandResult::text(e)).map_err(JsValue::from),
+    };
+    // Run against a snapshot, so a command registered mid-line can't pull the registry out from under it.
+    let registry = REGISTRY.with(|registry| registry.borrow().clone());
+    let columns = if columns == 0 { Terminal::default().columns } else { columns };
+    let terminal = SESSION.with(|cell| cell.borrow().terminal(columns));
+    let mut result = shell::run(&line, &mut WebHost { registry: &registry }, terminal).await;
+    if line != command {
+        // Like bash, show what `!!` and friends expanded to.
+        result.output = if result.output.is_empty() { line } else { format!("{}\n{}", line, result.output) };
+    }
+    save_session();
+    serde_wasm_bindgen::to_value(&result).map_err(JsValue::from)
+}
+
+/// The terminal's history, oldest first, for the arrow keys.
+#[wasm_bindgen]
+pub fn history() -> Vec<String> {
+    SESSION.with(|cell| cell.borrow().history().to_vec())
+}
+
+/// Reverse search: the index of the newest history line before `before` that
+/// contains `query`. Pass the last match to find the one before it.
+#[wasm_bindgen]
+pub fn search_history(query: String, before: usize) -> Option<usize> {
+    SESSION.with(|cell| cell.borrow().search(&query, before))
+}
+
+/// Completes the word before `cursor` (a character offset) in a terminal line.
+///
+/// Returns a `Completion`: `{ start, candidates, prefix }`.
+#[wasm_bindgen]
+pub fn complete(line: String, cursor: usize) -> Result<JsValue, JsValue> {
+    let registry = REGISTRY.with(|registry| registry.borrow().clone());
+    let completion = shell::complete::complete(&line, cursor, &registry, &WebSources);
+    serde_wasm_bindgen::to_value(&completion).map_err(JsValue::from)
+}
+
+struct WebSources;
+
+impl shell::complete::Sources for WebSources {
+    fn shaders(&self) -> Vec<String> {
+        RENDER_STATE.with(|cell| cell.borrow().as_ref().map(|state| state.get_shader_names()).unwrap_or_default())
+    }
+
+    fn params(&self) -> Vec<String> {
+        RENDER_STATE.with(|cell| cell.borrow().as_ref().and_then(|state| state.params()).map(|params| params.names()).unwrap_or_default())
+    }
+
+    fn paths(&self, partial: &str) -> Vec<String> {
+        VFS.with(|vfs| vfs.borrow().complete(partial))
+    }
+}
+
+struct WebHost<'a> {
+    registry: &'a Registry,
+}
+
+impl shell::Host for WebHost<'_> {
+    async fn run(&mut self, argv: &[String], stdin: Option<&str>, terminal: Option<Terminal>) -> Output {
+        self.registry.run(argv, stdin, terminal).await
+    }
+
+    fn write_file(&mut self, path: &str, contents: &str, append: bool) -> Result<(), String> {
+        VFS.with(|vfs| vfs.borrow_mut().write(path, contents, append)).map_err(|e| e.to_string())
+    }
+
+    fn raw(&self, name: &str) -> bool {
+        self.registry.get(name).is_some_and(|command| command.raw())
+    }
+
+    fn alias(&self, name: &str) -> Option<String> {
+        SESSION.with(|cell| cell.borrow().alias(name).map(str::to_string))
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        SESSION.with(|cell| cell.borrow().var(name).map(str::to_string))
+    }
+}
@@ -0,0 +1,454 @@
+//! The terminal commands that need the page: the fetched resume or the renderer.
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::command::{usage_error, Arg, ArgKind, Command, Context, Effect, Registry, RunFuture, Value};
+use crate::resume::Sections;
+use crate::shell::filters::strs;
+use crate::shader::header;
+use crate::shader::transition::{Kind, Transition};
+use crate::shell::Output;
+use crate::{fetch, renderer, resume};
+
+use super::RENDER_STATE;
+
+/// Adds every page command to `registry`.
+pub fn register(registry: &mut Registry) {
+    registry.register(Validate);
+    registry.register(View);
+    registry.register(Open);
+    registry.register(ResumeCommand);
+    registry.register(Python);
+    registry.register(Shaders);
+    registry.register(Shader);
+    registry.register(Clear);
+}
+
+const CONTACT_TARGETS: &[&str] = &["email", "github", "linkedin"];
+
+/// `resume.json`, or why it couldn't be fetched or read.
+async fn fetch_resume() -> Result<resume::Resume, Output> {
+    let json = fetch::fetch_text("./resume.json").await.map_err(fetch_error)?;
+    // The parse error names only the first problem; `validate` lists them all.
+    resume::Resume::from_json_str(&json).map_err(|e| Output::error(format!("resume.json: {} (run 'validate resume' for every problem)", e)))
+}
+
+/// Why fetching `resume.json` failed, from the message in `error`.
+fn fetch_error(error: JsValue) -> Output {
+    let reason = match error.dyn_ref::<js_sys::Error>() {
+        Some(error) => String::from(error.message()),
+        None => error.as_string().unwrap_or_else(|| format!("{:?}", error)),
+    };
+    Output::error(format!("Error fetching resume: {}", reason))
+}
+
+struct Validate;
+
+impl Command for Validate {
+    fn name(&self) -> &'static str {
+        "validate"
+    }
+
+    fn usage(&self) -> &'static str {
+        "validate resume"
+    }
+
+    fn description(&self) -> &'static str {
+        "Check resume.json against the schema"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "resume", kind: ArgKind::Positional(Value::OneOf(&["resume"])), help: "what to check" }]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            if strs(args) != ["resume"] {
+                return usage_error(self);
+            }
+            let json = match fetch::fetch_text("./resume.json").await {
+                Ok(json) => json,
+                Err(e) => return fetch_error(e),
+            };
+            let errors = resume::validate::validate_str(&json);
+            if errors.is_empty() {
+                return "resume.json: no problems found".into();
+            }
+            let list = errors.iter().map(|e| format!("  {}", e)).collect::<Vec<String>>().join("\n");
+            Output { stdout: format!("resume.json: {} problem(s)\n{}", errors.len(), list), status: 1, ..Output::default() }
+        })
+    }
+}
+
+struct View;
+
+impl Command for View {
+    fn name(&self) -> &'static str {
+        "view"
+    }
+
+    fn usage(&self) -> &'static str {
+        "view resume [SECTION]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Scroll the page to the resume or one of its sections"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "resume", kind: ArgKind::Positional(Value::OneOf(&["resume"])), help: "what to show" },
+            Arg { name: "SECTION", kind: ArgKind::Positional(Value::Section), help: "section to scroll to" },
+        ]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            match strs(args).as_slice() {
+                ["resume"] => Output::effect(Effect::ScrollToSection { section: None }),
+                ["resume", section] if Sections::NAMES.contains(section) => {
+                    Output::effect(Effect::ScrollToSection { section: Some(section.to_string()) })
+                }
+                ["resume", section] => Output::error(format!(
+                    "view: unknown section '{}'. Sections:\n  {}",
+                    section,
+                    Sections::NAMES.join("\n  ")
+                )),
+                _ => usage_error(self),
+            }
+        })
+    }
+}
+
+struct Open;
+
+impl Command for Open {
+    fn name(&self) -> &'static str {
+        "open"
+    }
+
+    fn usage(&self) -> &'static str {
+        "open email|github|linkedin"
+    }
+
+    fn description(&self) -> &'static str {
+        "Open a contact link from the resume"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "TARGET", kind: ArgKind::Positional(Value::OneOf(CONTACT_TARGETS)), help: "which link to open" }]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let [what] = args else {
+                return usage_error(self);
+            };
+            if !CONTACT_TARGETS.contains(&what.as_str()) {
+                return Output::error(format!("open: unknown target '{}' (try {})", what, CONTACT_TARGETS.join(", ")));
+            }
+            let resume = match fetch_resume().await {
+                Ok(resume) => resume,
+                Err(e) => return e,
+            };
+            let link = match what.as_str() {
+                "email" => Some(format!("mailto:{}", resume.contact.email)),
+                "github" => resume.contact.github,
+                _ => resume.contact.linkedin,
+            };
+            match link {
+                Some(link) => {
+                    let url = resume::url_for(&link);
+                    Output::text(format!("Opening {}", url)).with_effect(Effect::OpenUrl { url })
+                }
+                None => Output::error(format!("open: no {} link in resume.json", what)),
+            }
+        })
+    }
+}
+
+/// `resume`; named so it doesn't shadow the `resume` module.
+struct ResumeCommand;
+
+impl Command for ResumeCommand {
+    fn name(&self) -> &'static str {
+        "resume"
+    }
+
+    fn usage(&self) -> &'static str {
+        "resume --download-pdf | --export jsonresume"
+    }
+
+    fn description(&self) -> &'static str {
+        "Download the resume as PDF or export it as JSON Resume"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "--download-pdf", kind: ArgKind::Flag, help: "save the resume as resume.pdf" },
+            Arg { name: "--export", kind: ArgKind::Option(Value::OneOf(&["jsonresume"])), help: "print the resume in another schema" },
+        ]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let export = match strs(args).as_slice() {
+                ["--download-pdf"] => false,
+                ["--export", "jsonresume"] => true,
+                _ => return usage_error(self),
+            };
+            let resume = match fetch_resume().await {
+                Ok(resume) => resume,
+                Err(e) => return e,
+            };
+            if !export {
+                return Output::text("PDF download started.").with_effect(Effect::DownloadFile {
+                    filename: "resume.pdf".to_string(),
+                    mime: "application/pdf".to_string(),
+                    bytes: resume::pdf::render(&resume),
+                });
+            }
+            let conversion = resume::jsonresume::export(&resume);
+            let mut out = Output::text(serde_json::to_string_pretty(&conversion.value).unwrap_or_default());
+            out.stderr = conversion.warnings.iter().map(|w| format!("warning: {}", w)).collect::<Vec<String>>().join("\n");
+            out
+        })
+    }
+}
+
+struct Python;
+
+impl Command for Python {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["py"]
+    }
+
+    fn usage(&self) -> &'static str {
+        "python CODE"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run Python in the browser; the rest of the line is the code"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[Arg { name: "CODE", kind: ArgKind::Positional(Value::Text), help: "Python source, run with Pyodide" }]
+    }
+
+    fn raw(&self) -> bool {
+        true
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            match args {
+                [code] => Output::effect(Effect::RunPython { code: code.clone() }),
+                _ => usage_error(self),
+            }
+        })
+    }
+}
+
+struct Shaders;
+
+impl Command for Shaders {
+    fn name(&self) -> &'static str {
+        "shaders"
+    }
+
+    fn usage(&self) -> &'static str {
+        "shaders"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the background shaders"
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            if !args.is_empty() {
+                return usage_error(self);
+            }
+            RENDER_STATE.with(|cell| {
+                let state = cell.borrow();
+                let Some(state) = state.as_ref() else {
+                    return Output::error("Shaders are unavailable: the renderer failed to start");
+                };
+                let active = state.get_active_shader();
+                let shaders: Vec<_> = state.shaders().filter(|info| !info.hidden || info.id == active).collect();
+                let width = shaders.iter().map(|info| info.id.len()).max().unwrap_or(0);
+                let shader_list = shaders
+                    .iter()
+                    .map(|info| {
+                        let marker = if info.id == active { " (active)" } else { "" };
+                        format!("  {:width$}  {}: {}{}", info.id, info.name, info.description, marker, width = width)
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                let mut out = format!("Available shaders:\n{}\n\nUse 'shader [name]' to switch", shader_list);
+                if !state.failed_shaders().is_empty() {
+                    out.push_str("\n\nThese didn't compile in this browser:");
+                    for diagnostic in state.failed_shaders() {
+                        out.push_str(&format!("\n  {}", diagnostic));
+                    }
+                }
+                out.into()
+            })
+        })
+    }
+}
+
+struct Shader;
+
+/// `shader params`: the active shader's parameters with their values and ranges.
+fn list_params(state: &renderer::State) -> Output {
+    let name = state.get_active_shader();
+    let Some(params) = state.params().filter(|params| params.iter().next().is_some()) else {
+        return format!("{} has no parameters", name).into();
+    };
+    let width = params.iter().map(|(param, _)| param.name.len()).max().unwrap_or(0);
+    let mut out = format!("Parameters of {}:", name);
+    for (param, value) in params.iter() {
+        out.push_str(&format!(
+            "\n  {:width$}  {:<6} ({}..{}, default {})  {}",
+            param.name,
+            value,
+            param.min,
+            param.max,
+            param.default,
+            param.description,
+            width = width
+        ));
+    }
+    out.push_str("\n\nUse 'shader set PARAM VALUE' to change one");
+    out.into()
+}
+
+impl Command for Shader {
+    fn name(&self) -> &'static str {
+        "shader"
+    }
+
+    fn usage(&self) -> &'static str {
+        "shader NAME [--transition KIND] [--duration SECONDS] | shader params | shader set PARAM VALUE | shader reset | shader edit [NAME]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Switch the background shader or tune its parameters"
+    }
+
+    fn args(&self) -> &'static [Arg] {
+        &[
+            Arg { name: "NAME", kind: ArgKind::Positional(Value::Shader), help: "shader to switch to" },
+            Arg { name: "params", kind: ArgKind::Subcommand(&[]), help: "list the active shader's parameters" },
+            Arg {
+                name: "set",
+                kind: ArgKind::Subcommand(&[
+                    Arg { name: "PARAM", kind: ArgKind::Positional(Value::Param), help: "the parameter to change" },
+                    Arg { name: "VALUE", kind: ArgKind::Positional(Value::Text), help: "its new value" },
+                ]),
+                help: "change one of the active shader's parameters",
+            },
+            Arg { name: "reset", kind: ArgKind::Subcommand(&[]), help: "put its parameters back to their defaults" },
+            Arg {
+                name: "edit",
+                kind: ArgKind::Subcommand(&[Arg { name: "NAME", kind: ArgKind::Positional(Value::Shader), help: "shader to edit" }]),
+                help: "open a shader in the editor, the active one by default",
+            },
+            Arg { name: "--transition", kind: ArgKind::Option(Value::OneOf(Kind::NAMES)), help: "how to switch; crossfade by default" },
+            Arg { name: "--duration", kind: ArgKind::Option(Value::Text), help: "how long the transition takes, in seconds" },
+        ]
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move {
+            let (transition, args) = match Transition::from_args(&strs(args)) {
+                Ok(parsed) => parsed,
+                Err(e) => return Output::error(format!("shader: {}", e)),
+            };
+            match args.as_slice() {
+                ["params"] => with_state(|state| list_params(state)),
+                ["reset"] => with_state(|state| {
+                    if let Some(params) = state.params_mut() {
+                        params.reset();
+                    }
+                    Output::text(format!("Reset the parameters of {}", state.get_active_shader()))
+                }),
+                ["set", param, value] => match value.parse::<f32>() {
+                    Ok(value) => with_state(|state| match state.params_mut().map(|params| params.set(param, value)) {
+                        Some(Ok(())) => Output::text(format!("{} = {}", param, value)),
+                        Some(Err(e)) => Output::error(format!("shader: {}", e)),
+                        None => Output::error("shader: no shader is active"),
+                    }),
+                    Err(_) => Output::error(format!("shader: '{}' is not a number", value)),
+                },
+                ["edit"] => with_state(|state| edit(state, &state.get_active_shader())),
+                ["edit", name] => with_state(|state| edit(state, name)),
+                [name] if !header::SUBCOMMANDS.contains(name) => switch(name, transition),
+                _ => usage_error(self),
+            }
+        })
+    }
+}
+
+/// Runs `f` on the renderer, if it started.
+fn with_state(f: impl FnOnce(&mut renderer::State) -> Output) -> Output {
+    RENDER_STATE.with(|cell| match cell.borrow_mut().as_mut() {
+        Some(state) => f(state),
+        None => Output::error("Shaders are unavailable: the renderer failed to start"),
+    })
+}
+
+/// `shader NAME`.
+fn switch(name: &str, transition: Transition) -> Output {
+    let (known, available) = RENDER_STATE.with(|cell| match cell.borrow().as_ref() {
+        Some(state) => (state.has_shader(name), state.get_shader_names()),
+        None => (false, Vec::new()),
+    });
+    if known {
+        Output::text(format!("Switched to shader: {}", name)).with_effect(Effect::SetShader {
+            name: name.to_string(),
+            transition: transition.kind.name().to_string(),
+            duration_ms: (transition.duration * 1000.0).round() as u32,
+        })
+    } else {
+        Output::error(format!("Shader '{}' not found. Available shaders:\n  {}", name, available.join("\n  ")))
+    }
+}
+
+/// `shader edit NAME`: opens the editor on the source the shader is running.
+fn edit(state: &renderer::State, name: &str) -> Output {
+    match state.shaders().find(|info| info.id == name) {
+        Some(info) => Output::text(format!("Editing {}; Ctrl+S compiles and switches to it", name))
+            .with_effect(Effect::EditShader { name: name.to_string(), source: info.source.to_string() }),
+        None => Output::error(format!("shader: no shader named '{}' (see 'shaders')", name)),
+    }
+}
+
+struct Clear;
+
+impl Command for Clear {
+    fn name(&self) -> &'static str {
+        "clear"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["cls"]
+    }
+
+    fn usage(&self) -> &'static str {
+        "clear"
+    }
+
+    fn description(&self) -> &'static str {
+        "Clear the terminal"
+    }
+
+    fn run<'a>(&'a self, _ctx: Context<'a>, _args: &'a [String]) -> RunFuture<'a> {
+        Box::pin(async move { Output::effect(Effect::Clear) })
+    }
+}
@@ -1,4 +1,4 @@
-import init, { run_command, set_shader, get_active_shader } from './pkg/rendered_resume.js';
+import init, { run_command, complete, history, search_history, set_shader, get_active_shader, resume_pdf, load_shader, watch_shaders } from './pkg/rendered_resume.js';
 
 // --- Global State ---
 let terminal = null;
@@ -29,7 +29,7 @@ async function loadResumeContent() {
         // Summary Section
         if (sections.summary && sections.summary.enabled && resume.summary) {
             sectionsHTML += `
-                <div class="section">
+                <div class="section" id="section-summary">
                     <div class="section-title">
                         ${sections.summary.title}
                     </div>
@@ -44,7 +44,7 @@ async function loadResumeContent() {
             const hiddenExperiences = resume.experience.slice(2);
             
             sectionsHTML += `
-                <div class="section">
+                <div class="section" id="section-experience">
                     <div class="section-title">
                         ${sections.experience.title}
                     </div>
@@ -88,7 +88,7 @@ async function loadResumeContent() {
             const hiddenProjects = resume.projects.slice(2);
             
             sectionsHTML += `
-                <div class="section">
+                <div class="section" id="section-projects">
                     <div class="section-title">
                         ${sections.projects.title}
                     </div>
@@ -167,7 +167,7 @@ async function loadResumeContent() {
             
             if (skillCategories) {
                 sectionsHTML += `
-                    <div class="section">
+                    <div class="section" id="section-skills">
                         <div class="section-title">
                             ${sections.skills.title}
                         </div>
@@ -182,7 +182,7 @@ async function loadResumeContent() {
         // Education Section
         if (sections.education && sections.education.enabled && resume.education && resume.education.length > 0) {
             sectionsHTML += `
-                <div class="section">
+                <div class="section" id="section-education">
                     <div class="section-title">
                         ${sections.education.title}
                     </div>
@@ -259,45 +259,282 @@ window.toggleSection = toggleSection;
 
 // --- Terminal Command Handling ---
 const prompt = '$ ';
-let commandHistory = [];
-let historyIndex = -1;
+// History itself lives in the Rust session; this is only the arrow-key cursor into it,
+// clamped to the end of the history (a fresh line) whenever it's used.
+let historyIndex = Infinity;
 let currentCommand = "";
+let lastCompletion = null;
+// Set while Ctrl-R reverse search is open: { query, index } with index null for no match.
+let historySearch = null;
 
 async function handleCommand(commandStr) {
-    if (commandHistory[commandHistory.length - 1] !== commandStr) {
-        commandHistory.push(commandStr);
+    const result = await run_command(commandStr, terminal.cols);
+    historyIndex = history().length;
+    const effects = result.effects;
+    // A clear comes first and covers only what was printed before it, so it
+    // goes before the output rather than after.
+    if (effects.length > 0 && effects[0].type === 'clear') {
+        await applyEffect(effects.shift());
     }
-    historyIndex = commandHistory.length;
-
-    if (commandStr.trim().startsWith('python ')) {
-        const code = commandStr.trim().substring(7);
-        try {
-            let result = await pyodide.runPythonAsync(code);
-            if (result !== undefined) {
-                terminal.writeln(result);
+    if (result.output) {
+        terminal.writeln(result.output.replace(/\n/g, '\r\n'));
+    }
+    for (const effect of effects) {
+        await applyEffect(effect);
+    }
+}
+
+function drawSearch() {
+    const { query, index } = historySearch;
+    const match = index === null ? '' : history()[index];
+    const label = index === null && query ? 'failed reverse-i-search' : 'reverse-i-search';
+    terminal.write(`\x1b[2K\r(${label})\`${query}': ${match}`);
+}
+
+// Searches back from `before` (exclusive) for the current query.
+function searchHistory(before) {
+    const index = search_history(historySearch.query, before);
+    historySearch.index = index === undefined ? null : index;
+    drawSearch();
+}
+
+// Leaves reverse search with the match (if any) on the prompt.
+function endSearch() {
+    const { index } = historySearch;
+    if (index !== null) {
+        currentCommand = history()[index];
+        historyIndex = index;
+    }
+    historySearch = null;
+    terminal.write('\x1b[2K\r' + prompt + currentCommand);
+}
+
+function handleSearchKey(key, domEvent) {
+    if (domEvent.ctrlKey && domEvent.key === 'r') {
+        searchHistory(historySearch.index ?? history().length);
+    } else if (domEvent.ctrlKey && (domEvent.key === 'g' || domEvent.key === 'c')) {
+        historySearch.index = null;
+        endSearch();
+    } else if (domEvent.keyCode === 8) { // Backspace
+        historySearch.query = historySearch.query.slice(0, -1);
+        searchHistory(history().length);
+    } else if (domEvent.keyCode === 13 || domEvent.keyCode === 27 || domEvent.keyCode >= 37 && domEvent.keyCode <= 40) {
+        // Enter, Escape or an arrow: keep the match on the prompt; Enter then runs it.
+        endSearch();
+        return domEvent.keyCode === 13;
+    } else if (!domEvent.altKey && !domEvent.ctrlKey && !domEvent.metaKey && key.length === 1) {
+        historySearch.query += key;
+        searchHistory(history().length);
+    }
+    return false;
+}
+
+// Carries out one `Effect` from a `CommandResult`; see src/command.rs.
+async function applyEffect(effect) {
+    switch (effect.type) {
+        case 'set_shader':
+            set_shader(effect.name, effect.transition, effect.duration_ms);
+            break;
+        case 'edit_shader':
+            openShaderEditor(effect.name, effect.source);
+            break;
+        case 'clear':
+            terminal.clear();
+            break;
+        case 'download_file':
+            saveFile(effect.filename, effect.mime, effect.bytes);
+            break;
+        case 'open_url':
+            window.open(effect.url, '_blank', 'noopener');
+            break;
+        case 'scroll_to_section': {
+            const target = effect.section
+                ? document.getElementById(`section-${effect.section}`)
+                : document.getElementById('resume-content');
+            if (target) {
+                target.scrollIntoView({ behavior: 'smooth', block: 'start' });
+            } else {
+                terminal.writeln(`Section '${effect.section}' is not shown on this resume.`);
             }
-        } catch (error) {
-            terminal.writeln(error.message);
+            break;
         }
+        case 'run_python':
+            await runPython(effect.code);
+            break;
+        case 'more':
+            await page(effect.text);
+            break;
+        default:
+            console.warn('Unknown command effect:', effect);
+    }
+}
+
+// --- Shader editor for the `edit_shader` effect ---
+// Saving compiles the text as that shader; if it fails, the old one keeps running.
+let editedShader = null;
+
+function openShaderEditor(name, source) {
+    editedShader = name;
+    document.getElementById('shader-editor-title').textContent = `${name}.wgsl`;
+    const textarea = document.getElementById('shader-editor-source');
+    textarea.value = source;
+    showShaderStatus('Ctrl+S to compile, Esc to close');
+    document.getElementById('shader-editor').hidden = false;
+    textarea.focus();
+    textarea.setSelectionRange(0, 0);
+}
+
+function closeShaderEditor() {
+    document.getElementById('shader-editor').hidden = true;
+    editedShader = null;
+    terminal.focus();
+}
+
+async function saveShader() {
+    const name = editedShader;
+    const source = document.getElementById('shader-editor-source').value;
+    try {
+        await load_shader(name, source);
+        set_shader(name);
+        showShaderStatus(`Compiled ${name}.wgsl`);
+    } catch (error) {
+        // Rejections are diagnostics from src/shader/load.rs, unless something else went wrong.
+        const diagnostics = Array.isArray(error) ? error : [{ file: `${name}.wgsl`, message: String(error) }];
+        showShaderStatus(null, diagnostics);
+        const first = diagnostics.find(d => d.file === `${name}.wgsl` && d.line);
+        if (first) goToLine(first.line);
+    }
+}
+
+// Shows `message`, or lists `diagnostics`; those in the edited file jump to their line on click.
+function showShaderStatus(message, diagnostics = []) {
+    const status = document.getElementById('shader-editor-status');
+    status.innerHTML = '';
+    status.classList.toggle('failed', diagnostics.length > 0);
+    if (message) {
+        status.textContent = message;
+    }
+    for (const diagnostic of diagnostics) {
+        const row = document.createElement('div');
+        const where = diagnostic.line ? `${diagnostic.file}:${diagnostic.line}` : diagnostic.file;
+        row.textContent = `${where}: ${diagnostic.message}`;
+        if (diagnostic.report) row.title = diagnostic.report;
+        if (diagnostic.file === `${editedShader}.wgsl` && diagnostic.line) {
+            row.dataset.line = diagnostic.line;
+            row.addEventListener('click', () => goToLine(diagnostic.line));
+        }
+        status.appendChild(row);
+    }
+}
+
+// Selects 1-based `line` of the editor and scrolls it into view.
+function goToLine(line) {
+    const textarea = document.getElementById('shader-editor-source');
+    const lines = textarea.value.split('\n');
+    const start = lines.slice(0, line - 1).reduce((offset, text) => offset + text.length + 1, 0);
+    const end = start + (lines[line - 1] ?? '').length;
+    textarea.focus();
+    textarea.setSelectionRange(start, end);
+    const lineHeight = parseFloat(getComputedStyle(textarea).lineHeight);
+    textarea.scrollTop = Math.max(0, (line - 3) * lineHeight);
+}
+
+function initShaderEditor() {
+    const textarea = document.getElementById('shader-editor-source');
+    textarea.addEventListener('keydown', (e) => {
+        if ((e.ctrlKey || e.metaKey) && e.key === 's') {
+            e.preventDefault();
+            saveShader();
+        } else if (e.key === 'Escape') {
+            closeShaderEditor();
+        } else if (e.key === 'Tab' && !e.shiftKey) {
+            e.preventDefault();
+            textarea.setRangeText('    ', textarea.selectionStart, textarea.selectionEnd, 'end');
+        }
+    });
+    document.getElementById('shader-editor-save').addEventListener('click', saveShader);
+    document.getElementById('shader-editor-close').addEventListener('click', closeShaderEditor);
+}
+
+// --- Pager for the `more` effect ---
+// While a pager is open, keys page through it instead of editing the command line.
+let pager = null;
+
+function page(text) {
+    return new Promise(resolve => {
+        pager = { lines: text.replace(/\n$/, '').split('\n'), pos: 0, resolve };
+        showLines(terminal.rows - 1);
+    });
+}
+
+function showLines(count) {
+    terminal.write('\x1b[2K\r');
+    const end = Math.min(pager.lines.length, pager.pos + count);
+    for (; pager.pos < end; pager.pos++) {
+        terminal.writeln(pager.lines[pager.pos]);
+    }
+    if (pager.pos >= pager.lines.length) {
+        closePager();
         return;
     }
+    const percent = Math.floor(pager.pos * 100 / pager.lines.length);
+    terminal.write(`\x1b[7m--More--(${percent}%)\x1b[0m`);
+}
 
-    const output = await run_command(commandStr);
-    if (output) {
-        if (output.startsWith('__SET_SHADER__:')) {
-            const shaderName = output.split(':')[1];
-            set_shader(shaderName);
-            terminal.writeln(`Switched to shader: ${shaderName}`);
-        } else if (output === '__CLEAR__') {
-            terminal.clear();
-        } else if (output === '__DOWNLOAD_PDF__') {
-            downloadResumeAsPdf();
-        } else if (output === '__SHOW_RESUME__') {
-            // This is not handled yet.
+function closePager() {
+    terminal.write('\x1b[2K\r');
+    const { resolve } = pager;
+    pager = null;
+    resolve();
+}
+
+function handlePagerKey(key, domEvent) {
+    if (key === ' ') {
+        showLines(terminal.rows - 1);
+    } else if (domEvent.keyCode === 13 || domEvent.keyCode === 40 || key === 'j') {
+        showLines(1);
+    } else if (domEvent.keyCode === 27 || key === 'q') {
+        closePager();
+    }
+}
+
+async function runPython(code) {
+    if (!pyodide) {
+        terminal.writeln('Python runtime is still loading, try again in a moment.');
+        return;
+    }
+    try {
+        const result = await pyodide.runPythonAsync(code);
+        if (result !== undefined) {
+            terminal.writeln(String(result));
         }
-        else {
-            terminal.writeln(output.replace(/\n/g, '\r\n'));
+    } catch (error) {
+        terminal.writeln(error.message);
+    }
+}
+
+// Tab completion: fills in the common prefix, and lists the candidates when
+// pressed again without anything new to fill in.
+function completeCommand() {
+    const chars = Array.from(currentCommand);
+    let completion;
+    try {
+        completion = complete(currentCommand, chars.length);
+    } catch (error) {
+        return; // wasm not loaded yet
+    }
+    const completed = chars.slice(0, completion.start).join('') + completion.prefix;
+    if (completed !== currentCommand) {
+        currentCommand = completed;
+        terminal.write('\x1b[2K\r' + prompt + currentCommand);
+        lastCompletion = null;
+    } else if (completion.candidates.length > 1) {
+        if (lastCompletion === currentCommand) {
+            terminal.writeln('');
+            terminal.writeln(completion.candidates.join('  '));
+            terminal.write(prompt + currentCommand);
         }
+        lastCompletion = currentCommand;
     }
 }
 
@@ -308,6 +545,10 @@ function initTopTerminal() {
     const term = new window.Terminal({
         cursorBlink: true,
         convertEol: true,
+        // Commands print OSC 8 hyperlinks; open them without xterm's confirm prompt.
+        linkHandler: {
+            activate: (event, uri) => window.open(uri, '_blank', 'noopener'),
+        },
         fontFamily: "'Share Tech Mono', monospace",
         fontSize: 14,
         theme: {
@@ -339,35 +580,52 @@ function initTopTerminal() {
     });
 
     terminal.onKey(async ({ key, domEvent }) => {
+        if (pager) {
+            handlePagerKey(key, domEvent);
+            return;
+        }
+        if (historySearch && !handleSearchKey(key, domEvent)) {
+            return;
+        }
         const printable = !domEvent.altKey && !domEvent.ctrlKey && !domEvent.metaKey;
 
-        if (domEvent.keyCode === 13) { // Enter
+        if (domEvent.ctrlKey && domEvent.key === 'r') {
+            domEvent.preventDefault();
+            historySearch = { query: '', index: null };
+            drawSearch();
+        } else if (domEvent.keyCode === 13) { // Enter
             terminal.write('\r\n');
             if (currentCommand.length > 0) {
                 await handleCommand(currentCommand);
                 currentCommand = '';
             }
             terminal.write(prompt);
+        } else if (domEvent.keyCode === 9) { // Tab
+            domEvent.preventDefault();
+            completeCommand();
         } else if (domEvent.keyCode === 8) { // Backspace
             if (currentCommand.length > 0) {
                 currentCommand = currentCommand.slice(0, -1);
                 terminal.write('\b \b');
             }
         } else if (domEvent.keyCode === 38) { // Up arrow
+            const entries = history();
+            historyIndex = Math.min(historyIndex, entries.length);
             if (historyIndex > 0) {
                 historyIndex--;
-                const prevCommand = commandHistory[historyIndex];
+                const prevCommand = entries[historyIndex];
                 terminal.write('\x1b[2K\r' + prompt + prevCommand);
                 currentCommand = prevCommand;
             }
         } else if (domEvent.keyCode === 40) { // Down arrow
-            if (historyIndex < commandHistory.length - 1) {
+            const entries = history();
+            if (historyIndex < entries.length - 1) {
                 historyIndex++;
-                const nextCommand = commandHistory[historyIndex];
+                const nextCommand = entries[historyIndex];
                 terminal.write('\x1b[2K\r' + prompt + nextCommand);
                 currentCommand = nextCommand;
             } else {
-                historyIndex = commandHistory.length;
+                historyIndex = entries.length;
                 terminal.write('\x1b[2K\r' + prompt);
                 currentCommand = "";
             }
@@ -432,188 +690,57 @@ window.addEventListener('resize', fitTerminal);
 
 
 
+function saveFile(filename, mime, bytes) {
+    const blob = new Blob([bytes], { type: mime });
+    const url = URL.createObjectURL(blob);
+    const link = document.createElement('a');
+    link.href = url;
+    link.download = filename;
+    link.click();
+    URL.revokeObjectURL(url);
+}
+
 async function downloadResumeAsPdf() {
-    terminal.writeln("Generating balanced single-page PDF...");
+    terminal.writeln("Generating PDF...");
 
     try {
-        const { jsPDF } = window.jspdf;
-        const doc = new jsPDF('p', 'pt', 'a4');
-
-        const resumeResponse = await fetch('./resume.json?t=' + Date.now());
-        const resume = await resumeResponse.json();
-
-        // --- Constants (Balanced B&W) ---
-        const TEXT_COLOR = '#000000';
-        const MARGIN = 40;
-        const PAGE_WIDTH = doc.internal.pageSize.getWidth();
-        const CONTENT_WIDTH = PAGE_WIDTH - MARGIN * 2;
-
-        let y = 0;
-        
-        // --- Header ---
-        y = MARGIN;
-        doc.setFont('helvetica', 'bold');
-        doc.setFontSize(24);
-        doc.setTextColor(TEXT_COLOR);
-        doc.text(resume.contact.name, PAGE_WIDTH / 2, y, { align: 'center' });
-        y += 24;
-
-        doc.setFont('helvetica', 'normal');
-        doc.setFontSize(11);
-        doc.text(resume.contact.title, PAGE_WIDTH / 2, y, { align: 'center' });
-        y += 16;
-
-        doc.setFontSize(10);
-        const contactInfo = [resume.contact.email, resume.contact.phone, resume.contact.github, resume.contact.location].filter(Boolean).join('  •  ');
-        doc.text(contactInfo, PAGE_WIDTH / 2, y, { align: 'center' });
-        y += 22;
-
-        doc.setDrawColor(TEXT_COLOR);
-        doc.setLineWidth(0.7);
-        doc.line(MARGIN, y, PAGE_WIDTH - MARGIN, y);
-        y += 22;
-
-        // --- Section Helper ---
-        const drawSection = (title, body) => {
-            doc.setFont('helvetica', 'bold');
-            doc.setFontSize(12);
-            doc.text(title.toUpperCase(), MARGIN, y);
-            y += 15;
-            body();
-            y += 15;
-        };
-
-        // --- Summary ---
-        if (resume.sections.summary && resume.sections.summary.enabled) {
-            drawSection(resume.sections.summary.title, () => {
-                doc.setFont('helvetica', 'normal');
-                doc.setFontSize(10);
-                const summaryLines = doc.splitTextToSize(resume.summary, CONTENT_WIDTH);
-                doc.text(summaryLines, MARGIN, y);
-                y += summaryLines.length * 12;
-            });
-        }
-
-        // --- Experience ---
-        if (resume.sections.experience && resume.sections.experience.enabled) {
-            drawSection(resume.sections.experience.title, () => {
-                resume.experience.forEach(exp => {
-                    doc.setFont('helvetica', 'bold');
-                    doc.setFontSize(10.5);
-                    doc.text(exp.title, MARGIN, y);
-
-                    doc.setFont('helvetica', 'normal');
-                    doc.text(exp.dates, CONTENT_WIDTH + MARGIN, y, { align: 'right' });
-                    y += 13;
-
-                    doc.setFont('helvetica', 'italic');
-                    doc.setFontSize(10);
-                    doc.text(exp.company, MARGIN, y);
-                    y += 13;
-
-                    doc.setFont('helvetica', 'normal');
-                    const descLines = doc.splitTextToSize(exp.description, CONTENT_WIDTH);
-                    doc.text(descLines, MARGIN, y);
-                    y += descLines.length * 12 + 8;
-                });
-            });
-        }
-
-        // --- Projects ---
-        if (resume.sections.projects && resume.sections.projects.enabled) {
-            drawSection(resume.sections.projects.title, () => {
-                resume.projects.forEach(proj => {
-                    doc.setFont('helvetica', 'bold');
-                    doc.setFontSize(10.5);
-                    doc.text(proj.name, MARGIN, y);
-                    y += 13;
-
-                    doc.setFont('helvetica', 'normal');
-                    const descLines = doc.splitTextToSize(proj.description, CONTENT_WIDTH);
-                    doc.text(descLines, MARGIN, y);
-                    y += descLines.length * 12;
-
-                    if (proj.github) {
-                        doc.textWithLink(`GitHub: ${proj.github}`, MARGIN, y, { url: `https://${proj.github}` });
-                        y += 12;
-                    }
-                    y += 8;
-                });
-            });
-        }
-
-        // --- Skills ---
-        if (resume.sections.skills && resume.sections.skills.enabled) {
-            drawSection(resume.sections.skills.title, () => {
-                for (const category in resume.skills) {
-                    if (resume.sections.skills.categories[category] && resume.skills[category].length > 0) {
-                        doc.setFont('helvetica', 'bold');
-                        doc.setFontSize(10.5);
-                        doc.text(resume.sections.skills.categories[category], MARGIN, y);
-                        y += 13;
-
-                        doc.setFont('helvetica', 'normal');
-                        doc.setFontSize(10);
-                        const skillsString = resume.skills[category].join('  •  ');
-                        const skillLines = doc.splitTextToSize(skillsString, CONTENT_WIDTH);
-                        doc.text(skillLines, MARGIN, y);
-                        y += skillLines.length * 12 + 8;
-                    }
-                }
-            });
-        }
-
-        // --- Education ---
-        if (resume.sections.education && resume.sections.education.enabled) {
-            drawSection(resume.sections.education.title, () => {
-                resume.education.forEach(edu => {
-                    doc.setFont('helvetica', 'bold');
-                    doc.setFontSize(10.5);
-                    doc.text(edu.degree, MARGIN, y);
-
-                    doc.setFont('helvetica', 'normal');
-                    doc.text(edu.dates, CONTENT_WIDTH + MARGIN, y, { align: 'right' });
-                    y += 13;
-
-                    doc.setFont('helvetica', 'italic');
-                    doc.setFontSize(10);
-                    doc.text(edu.university, MARGIN, y);
-                    y += 13;
-                });
-            });
-        }
-
-        doc.save('resume_one_page.pdf');
+        saveFile('resume.pdf', 'application/pdf', await resume_pdf());
         terminal.writeln("PDF download started.");
     } catch (error) {
-        terminal.writeln(`Error generating PDF: ${error.message}`);
+        const message = error && error.message ? error.message : error;
+        terminal.writeln(`Error generating PDF: ${message}`);
         console.error("PDF Generation Error:", error);
     }
 }
 
-window.setup_shader_switcher = (names) => {
+window.setup_shader_switcher = (shaders) => {
     const select = document.getElementById('shader-select');
     if (!select) return;
 
+    const selected = select.value;
     select.innerHTML = '';
-    for (const name of names) {
+    for (const [id, name, description] of shaders) {
         const option = document.createElement('option');
-        option.value = name;
+        option.value = id;
         option.textContent = name;
+        option.title = description;
         select.appendChild(option);
     }
 
+    if (selected) select.value = selected;
     select.style.display = 'block';
 
-    select.addEventListener('change', (event) => {
+    // Called again whenever a shader is loaded, so don't stack listeners.
+    select.onchange = (event) => {
         set_shader(event.target.value);
-    });
+    };
 };
 
 // --- Main Application Initialization ---
 async function main() {
     await loadResumeContent();
     initTopTerminal();
+    initShaderEditor();
 
     document.getElementById('download-pdf-btn').addEventListener('click', downloadResumeAsPdf);
 
@@ -629,6 +756,11 @@ async function main() {
         console.log('Attempting WebGPU/WebGL initialization...');
         await init();
         console.log('WASM renderer initialized successfully.');
+        // Locally, or with ?dev, pick up edits to src/shaders without a rebuild.
+        const local = ['localhost', '127.0.0.1'].includes(location.hostname);
+        if (local || new URLSearchParams(location.search).has('dev')) {
+            watch_shaders(1000);
+        }
     } catch (error) {
         console.warn('WASM renderer initialization failed:', error.message);
         // Site continues to work without background shaders